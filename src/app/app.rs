@@ -2,6 +2,7 @@ use {
     anyhow::Result,
     crate::{
         display::W,
+        edit,
         help,
         play,
     },
@@ -40,6 +41,10 @@ impl App {
         debug!("fromage: {:?}", &fromage);
         use StateTransition::*;
         self.states.push(initial_state::make(&fromage)?);
+        // the player is chosen before the first screen is shown
+        if let Some(state) = initial_state::profile_state(&fromage)? {
+            self.states.push(state);
+        }
         let mut con = Context::new(dam, w, &fromage);
         loop {
             let label = self.current_state().label();
@@ -47,14 +52,32 @@ impl App {
             match self.current_state().run(&mut con)? {
                 PlayLevel{level_idx} => {
                     if let Some(level) = self.current_state().get_level(level_idx) {
+                        let ruleset = self.current_state().get_ruleset(level_idx);
+                        let seed = self.current_state().get_seed(level_idx).or(fromage.seed());
+                        let recorded = self.current_state().records_games(level_idx);
                         self.states.push(Box::new(
                             play::PlayLevelState::new(
                                 &level,
+                                ruleset,
                                 Some(label),
+                                seed,
+                                recorded,
                             )?
                         ));
                     }
                 }
+                EditLevel{level_idx} => {
+                    let state = self.current_state();
+                    if let (Some(path), Some(level)) = (
+                        state.get_level_path(level_idx),
+                        state.get_level(level_idx),
+                    ) {
+                        let ruleset = state.get_ruleset(level_idx);
+                        self.states.push(Box::new(
+                            edit::LevelEditor::new(path, level, None, ruleset)
+                        ));
+                    }
+                }
                 Help => {
                     self.states.push(Box::new(
                         help::default_view()
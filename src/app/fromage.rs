@@ -23,6 +23,10 @@ pub enum Command {
     Play(PlayCommand),
     Edit(EditCommand),
     Campaign(CampaignCommand),
+    Solve(SolveCommand),
+    Replay(ReplayCommand),
+    Stats(StatsCommand),
+    Generate(GenerateCommand),
 }
 
 impl Fromage {
@@ -30,9 +34,17 @@ impl Fromage {
         match &self.command {
             Some(Command::Play(c)) => c.color_blind,
             Some(Command::Edit(c)) => c.color_blind,
+            Some(Command::Replay(c)) => c.color_blind,
             _ => false,
         }
     }
+    /// the seed of the world, when given
+    pub fn seed(&self) -> Option<usize> {
+        match &self.command {
+            Some(Command::Play(c)) => c.seed,
+            _ => None,
+        }
+    }
 }
 
 #[derive(FromArgs, PartialEq, Debug, Default, Clone)]
@@ -44,13 +56,22 @@ pub struct PlayCommand {
     /// use letters for actors
     pub color_blind: bool,
 
+    /// seed of the world, to get reproducible games
+    #[argh(option)]
+    pub seed: Option<usize>,
+
+    /// name of the player, whose wins are recorded in their
+    /// own profile (created if needed)
+    #[argh(option)]
+    pub player: Option<String>,
+
     #[argh(positional)]
     /// optional path to a level file
     pub path: Option<PathBuf>,
 }
 
 #[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
-/// create/edit a level
+/// create/edit a level, or edit a campaign
 #[argh(subcommand, name = "edit")]
 pub struct EditCommand {
 
@@ -67,6 +88,95 @@ pub struct EditCommand {
     pub path: PathBuf,
 }
 
+#[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
+/// check a level (or all levels of a campaign) can be won
+#[argh(subcommand, name = "solve")]
+pub struct SolveCommand {
+
+    /// seed given to the world at its first turn (default 0)
+    #[argh(option)]
+    pub seed: Option<usize>,
+
+    /// max number of lapin moves of a solution
+    #[argh(option)]
+    pub max_depth: Option<usize>,
+
+    /// max number of explored states before giving up
+    #[argh(option)]
+    pub max_states: Option<usize>,
+
+    /// max number of seconds spent on a level before giving up
+    #[argh(option)]
+    pub max_seconds: Option<u64>,
+
+    #[argh(positional)]
+    /// path to the level or campaign file
+    pub path: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
+/// print metrics of a level (or of all levels of a campaign)
+/// and an estimate of their difficulty
+#[argh(subcommand, name = "stats")]
+pub struct StatsCommand {
+
+    #[argh(positional)]
+    /// path to the level or campaign file
+    pub path: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Debug, Clone)]
+/// build a random level, checked to be winnable
+#[argh(subcommand, name = "generate")]
+pub struct GenerateCommand {
+
+    /// seed of the generator (default 0)
+    #[argh(option)]
+    pub seed: Option<usize>,
+
+    /// width of the level
+    #[argh(option)]
+    pub width: Option<i32>,
+
+    /// height of the level
+    #[argh(option)]
+    pub height: Option<i32>,
+
+    /// percentages of terrains, e.g. "stone=10,water=8,sand=5"
+    #[argh(option)]
+    pub terrains: Option<String>,
+
+    /// max numbers of enemies, e.g. "fox=3,hunter=1"
+    #[argh(option)]
+    pub enemies: Option<String>,
+
+    /// the wanted difficulty, as estimated by `lapin stats`
+    #[argh(option)]
+    pub difficulty: Option<f32>,
+
+    /// format of the written level file (same as extension if not precised)
+    #[argh(option, short='f')]
+    pub output_format: Option<String>, // argh doesn't support enum as values :(
+
+    #[argh(positional)]
+    /// path to the level file to create
+    pub path: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
+/// play again a recorded game
+#[argh(subcommand, name = "replay")]
+pub struct ReplayCommand {
+
+    #[argh(switch, short='c')]
+    /// use letters for actors
+    pub color_blind: bool,
+
+    #[argh(positional)]
+    /// path to the replay file
+    pub path: PathBuf,
+}
+
 #[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
 /// campaign building/packing tool
 #[argh(subcommand, name = "campaign")]
@@ -83,6 +193,10 @@ pub struct CampaignCommand {
 pub enum CampaignSubCommand {
     New(NewCampaignCommand),
     Pack(PackCampaignCommand),
+    Unpack(UnpackCampaignCommand),
+    List(ListCampaignCommand),
+    Info(InfoCampaignCommand),
+    Check(CheckCampaignCommand),
 }
 
 #[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
@@ -105,6 +219,10 @@ pub struct NewCampaignCommand {
 #[argh(subcommand, name = "pack")]
 pub struct PackCampaignCommand {
 
+    #[argh(switch)]
+    /// pack the levels even when the validator finds issues
+    pub force: bool,
+
     #[argh(positional)]
     /// path to the unpacked file
     pub unpacked_path: PathBuf,
@@ -115,4 +233,60 @@ pub struct PackCampaignCommand {
 
 }
 
+#[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
+/// write all levels of a campaign file into their own files
+#[argh(subcommand, name = "unpack")]
+pub struct UnpackCampaignCommand {
+
+    #[argh(switch)]
+    /// overwrite the files which already exist
+    pub force: bool,
+
+    /// format of the written level files (json if not precised)
+    #[argh(option, short='f')]
+    pub output_format: Option<String>,
+
+    #[argh(positional)]
+    /// path to the packed file
+    pub packed_path: PathBuf,
+
+    #[argh(positional)]
+    /// directory where to write the campaign and level files
+    pub dir: PathBuf,
+
+}
+
+#[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
+/// list the campaigns and levels contained in a file
+#[argh(subcommand, name = "list")]
+pub struct ListCampaignCommand {
+
+    #[argh(positional)]
+    /// path to the campaign (or level) file
+    pub path: PathBuf,
+
+}
+
+#[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
+/// print the levels of a campaign with their signature and win status
+#[argh(subcommand, name = "info")]
+pub struct InfoCampaignCommand {
+
+    #[argh(positional)]
+    /// path to the campaign file
+    pub path: PathBuf,
+
+}
+
+#[derive(FromArgs, PartialEq, Eq, Debug, Clone)]
+/// check all the levels referenced by a campaign can be found
+#[argh(subcommand, name = "check")]
+pub struct CheckCampaignCommand {
+
+    #[argh(positional)]
+    /// path to the campaign file
+    pub path: PathBuf,
+
+}
+
 
@@ -5,14 +5,24 @@ use {
             LoadedCampaign,
             LoadOrigin,
         },
+        campaign_edit::CampaignEditor,
         choose,
         edit,
         included,
         play,
+        profile::ChooseProfileState,
         persist::{
             self,
             Bag,
         },
+        replay::{
+            Replay,
+            ReplayState,
+        },
+        win_db::{
+            self,
+            WinFile,
+        },
     },
     std::{
         convert::TryFrom,
@@ -24,10 +34,11 @@ use {
 /// Determine the first state
 pub fn make(fromage: &Fromage) -> Result<Box<dyn State>> {
     match &fromage.command {
-        Some(Command::Edit ( ec )) => Ok(Box::new(
-            edit::LevelEditor::try_from(ec)?
-        )),
+        Some(Command::Edit ( ec )) => edit_state(ec),
         Some(Command::Play ( pc )) => play_state(pc),
+        Some(Command::Replay ( rc )) => Ok(Box::new(
+            ReplayState::new(Replay::load(&rc.path)?)
+        )),
         _ => default_state(),
     }
 }
@@ -39,13 +50,25 @@ fn default_state() -> Result<Box<dyn State>> {
     )?))
 }
 
+/// open the campaign editor or the level editor, depending on
+/// the content of the file
+fn edit_state(ec: &EditCommand) -> Result<Box<dyn State>> {
+    if ec.path.exists() {
+        let bag: Bag = persist::read_bag_file(&ec.path)?;
+        if bag.is_campaign() {
+            return Ok(Box::new(CampaignEditor::new(&ec.path, bag)?));
+        }
+    }
+    Ok(Box::new(edit::LevelEditor::try_from(ec)?))
+}
+
 /// compute the relevant initial state for a `lapin play` command
 /// (may be choosing a level or directly playing it)
 fn play_state(pc: &PlayCommand) -> Result<Box<dyn State>> {
     if let Some(path) = &pc.path {
-        let mut bag: Bag = persist::read_file(&path)?;
+        let mut bag: Bag = persist::read_bag_file(&path)?;
         if let Some(level) = bag.as_sole_level() {
-            Ok(Box::new(play::PlayLevelState::new(&level, None)?))
+            Ok(Box::new(play::PlayLevelState::new(&level, None, None, pc.seed, true)?))
         } else if bag.is_campaign() {
             let loaded_campaign = LoadedCampaign::load(&path, bag, LoadOrigin::Bag)?;
             Ok(Box::new(choose::ChooseLevelState::new(loaded_campaign)?))
@@ -56,3 +79,23 @@ fn play_state(pc: &PlayCommand) -> Result<Box<dyn State>> {
         default_state()
     }
 }
+
+/// when playing, select the player given with `--player`, or
+/// return a state letting the user choose between the known players
+pub fn profile_state(fromage: &Fromage) -> Result<Option<Box<dyn State>>> {
+    match &fromage.command {
+        Some(Command::Play(PlayCommand { player: Some(player), .. })) => {
+            win_db::select_profile(player)?;
+            Ok(None)
+        }
+        None | Some(Command::Play(_)) => {
+            let win_file = WinFile::load()?;
+            if !win_file.profile_names().is_empty() {
+                Ok(Some(Box::new(ChooseProfileState::new(&win_file))))
+            } else {
+                Ok(None)
+            }
+        }
+        _ => Ok(None),
+    }
+}
@@ -13,6 +13,9 @@ pub use {
     context::Context,
     fromage::*,
     state::State,
-    task_sync::Dam,
+    task_sync::{
+        Dam,
+        DamEvent,
+    },
     transition::StateTransition,
 };
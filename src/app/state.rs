@@ -2,8 +2,10 @@
 use {
     anyhow::Result,
     crate::{
+        core::Ruleset,
         persist::Level,
     },
+    std::path::PathBuf,
     super::{
         Context,
         transition::StateTransition,
@@ -39,5 +41,41 @@ pub trait State {
         &self,
         level_idx: usize,
     ) -> Option<Level>;
+
+    /// provide the ruleset of the campaign, which applies
+    /// to the level when it doesn't have its own one
+    fn get_ruleset(
+        &self,
+        _level_idx: usize,
+    ) -> Option<Ruleset> {
+        None
+    }
+
+    /// provide the seed the world must play with, when
+    /// the level is only known to be winnable with it
+    fn get_seed(
+        &self,
+        _level_idx: usize,
+    ) -> Option<usize> {
+        None
+    }
+
+    /// tell whether the wins and replays of the games of
+    /// the level should be saved
+    fn records_games(
+        &self,
+        _level_idx: usize,
+    ) -> bool {
+        true
+    }
+
+    /// provide the path where the level should be saved, for
+    /// states letting the user edit the levels they list
+    fn get_level_path(
+        &self,
+        _level_idx: usize,
+    ) -> Option<PathBuf> {
+        None
+    }
 }
 
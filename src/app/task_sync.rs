@@ -2,32 +2,118 @@ use {
     anyhow::Result,
     crossbeam::channel::{
         after,
+        unbounded,
         Receiver,
+        Sender,
+    },
+    crossterm::{
+        event::{
+            self,
+            Event as CrosstermEvent,
+            MouseButton,
+            MouseEvent,
+        },
+        terminal,
     },
     std::{
+        thread,
         time::{
             Duration,
+            Instant,
         },
     },
     termimad::{
         Event,
-        EventSource,
     },
 };
 
+/// the max delay between two clicks at the same place
+/// for them to make a double click (the same as termimad)
+const DOUBLE_CLICK_MAX_DURATION: Duration = Duration::from_millis(700);
+
+/// an event read by the dam: the crossterm one, and the termimad
+/// one it's normalized into, when there's one (mouse presses and
+/// drags have none)
+#[derive(Debug, Clone, Copy)]
+pub struct DamEvent {
+    pub raw: CrosstermEvent,
+    pub event: Option<Event>,
+}
+
+impl DamEvent {
+    /// tell whether the event is of interest for the application,
+    /// either because it's convertible into a termimad event or
+    /// because it's a part of a mouse drag
+    fn is_relevant(&self) -> bool {
+        match self.raw {
+            CrosstermEvent::Mouse(MouseEvent::Down(MouseButton::Left, ..)) => true,
+            CrosstermEvent::Mouse(MouseEvent::Drag(MouseButton::Left, ..)) => true,
+            _ => self.event.is_some(),
+        }
+    }
+}
+
 /// The dam controls the flow of events.
+///
+/// It reads the terminal events in its own thread, instead of
+/// using termimad's `EventSource`, because the event source drops
+/// the mouse presses and drags the pen of the editor needs, and
+/// two threads can't both read the events of the terminal.
+///
+/// The events are still normalized by termimad, and the double
+/// clicks are detected the same way. What's lost is the shared
+/// event count of the event source, which lapin doesn't use.
 pub struct Dam {
-    event_source: EventSource,
-    receiver: Receiver<Event>,
-    in_dam: Option<Event>,
+    tx_quit: Sender<bool>,
+    receiver: Receiver<DamEvent>,
+    in_dam: Option<DamEvent>,
 }
 
 impl Dam {
     pub fn new() -> Result<Self> {
-        let event_source = EventSource::new()?;
-        let receiver = event_source.receiver();
+        let (tx_events, receiver) = unbounded();
+        let (tx_quit, rx_quit) = unbounded();
+        terminal::enable_raw_mode()?;
+        thread::spawn(move || {
+            let mut last_click: Option<(Instant, u16, u16)> = None;
+            loop {
+                let raw = match event::read() {
+                    Ok(raw) => raw,
+                    Err(e) => {
+                        warn!("error while reading event: {:?}", e);
+                        continue;
+                    }
+                };
+                let mut dam_event = DamEvent {
+                    raw,
+                    event: Event::from_crossterm_event(Ok(raw)),
+                };
+                if !dam_event.is_relevant() {
+                    continue;
+                }
+                if let Some(Event::Click(x, y, ..)) = dam_event.event {
+                    if let Some((time, last_x, last_y)) = last_click {
+                        if last_x == x && last_y == y && time.elapsed() < DOUBLE_CLICK_MAX_DURATION {
+                            dam_event.event = Some(Event::DoubleClick(x, y));
+                        }
+                    }
+                    last_click = Some((Instant::now(), x, y));
+                } else if dam_event.event.is_some() {
+                    last_click = None;
+                }
+                if tx_events.send(dam_event).is_err() {
+                    return; // nobody listens anymore
+                }
+                match rx_quit.recv() {
+                    Ok(false) => {}
+                    _ => {
+                        return;
+                    }
+                }
+            }
+        });
         Ok(Self {
-            event_source,
+            tx_quit,
             receiver,
             in_dam: None,
         })
@@ -52,12 +138,13 @@ impl Dam {
         !self.receiver.is_empty()
     }
 
-    /// block until next event (including the one which
-    ///  may have been pushed back into the dam).
+    /// block until next terminal event (including the one which
+    ///  may have been pushed back into the dam), mouse presses
+    ///  and drags included.
     /// no event means the source is dead (i.e. we
-    /// must quit broot)
+    /// must quit)
     /// There's no event kept in dam after this call.
-    pub fn next_event(&mut self) -> Option<Event> {
+    pub fn next_dam_event(&mut self) -> Option<DamEvent> {
         if self.in_dam.is_some() {
             self.in_dam.take()
         } else {
@@ -71,14 +158,34 @@ impl Dam {
         }
     }
 
+    /// block until next event which termimad understands,
+    /// skipping (and unblocking) the other ones.
+    pub fn next_event(&mut self) -> Option<Event> {
+        loop {
+            if let Some(event) = self.next_dam_event()?.event {
+                return Some(event);
+            }
+            self.unblock();
+        }
+    }
+
     pub fn unblock(&mut self) {
-        self.event_source.unblock(false);
+        if let Err(e) = self.tx_quit.send(false) {
+            warn!("dam can't unblock: {:?}", e);
+        }
     }
 
     pub fn kill(&mut self) {
-        self.event_source.unblock(true);
-        let event_source_end = self.event_source.receiver().recv();
+        let _ = self.tx_quit.send(true);
+        let event_source_end = self.receiver.recv();
         debug!("event_source_end : {:?}", event_source_end);
     }
 }
 
+impl Drop for Dam {
+    fn drop(&mut self) {
+        if let Err(e) = terminal::disable_raw_mode() {
+            warn!("error while disabling raw mode: {:?}", e);
+        }
+    }
+}
@@ -8,6 +8,9 @@ pub enum StateTransition {
     PlayLevel {
         level_idx: usize,
     },
+    EditLevel {
+        level_idx: usize,
+    },
     Help,
     Back,
     Quit,
@@ -1,8 +1,22 @@
 
 use {
+    anyhow::Result,
+    crate::core::Ruleset,
     serde::{Serialize, Deserialize},
 };
 
+/// check the key of a level can be used as a file stem, so
+/// that the level can be written in the directory of the campaign
+pub fn check_level_key(key: &str) -> Result<()> {
+    if key.is_empty() {
+        Err(anyhow!("A key can't be empty"))
+    } else if key.contains(['/', '\\', '.', ':']) {
+        Err(anyhow!("A key must be usable as a file stem"))
+    } else {
+        Ok(())
+    }
+}
+
 
 /// a campaign, that is mostly a list of reference
 /// to levels.
@@ -15,8 +29,7 @@ pub struct Campaign {
 
     pub name: String,
 
-    /// a description, not yet used, but which could be in the future
-    /// (reserved for easier compatibility)
+    /// a description (markdown), shown in the level chooser
     pub description: String,
 
     /// whether the user can play any level without having
@@ -26,4 +39,29 @@ pub struct Campaign {
     /// levels in the order they should be done
     pub levels: Vec<String>,
 
+    /// the rules of the levels which don't have their own ones
+    /// (when none, the default rules apply)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ruleset: Option<Ruleset>,
+
+    /// when set, levels are unlocked by the total of stars won in
+    /// the campaign instead of by winning the previous level: the
+    /// level at index i needs i times this number of stars
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stars_per_level: Option<usize>,
+
+}
+
+#[cfg(test)]
+mod campaign_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_level_keys() {
+        assert!(check_level_key("meadow-3").is_ok());
+        for key in &["", "../../.bashrc", "/etc/x", "a.b", "a\\b", "C:x"] {
+            assert!(check_level_key(key).is_err(), "{:?} should be refused", key);
+        }
+    }
 }
@@ -0,0 +1,127 @@
+use {
+    anyhow::Result,
+    crate::{
+        app::{
+            CheckCampaignCommand,
+            InfoCampaignCommand,
+            ListCampaignCommand,
+        },
+        persist::{
+            self,
+            Bag,
+        },
+        win_db::{
+            Signature,
+            WinFile,
+        },
+    },
+    std::path::Path,
+    super::*,
+};
+
+fn read_campaign_bag(path: &Path) -> Result<Bag> {
+    let bag: Bag = persist::read_bag_file(path)?;
+    if !bag.is_campaign() {
+        return Err(anyhow!("no campaign found in bag"));
+    }
+    Ok(bag)
+}
+
+fn origin_label(origin: Option<LoadOrigin>) -> &'static str {
+    match origin {
+        Some(LoadOrigin::Bag) => "bag",
+        Some(LoadOrigin::External) => "file",
+        None => "missing",
+    }
+}
+
+/// print the campaigns and levels contained in a file
+pub fn list(lcc: &ListCampaignCommand) -> Result<()> {
+    let bag: Bag = persist::read_bag_file(&lcc.path)?;
+    for campaign in &bag.campaigns {
+        println!("campaign {:?} ({} levels)", campaign.name, campaign.levels.len());
+        for key in &campaign.levels {
+            println!("    {}", key);
+        }
+    }
+    if !bag.levels.is_empty() {
+        println!("levels in the file:");
+        let mut keys: Vec<&String> = bag.levels.keys().collect();
+        keys.sort();
+        for key in keys {
+            println!("    {:?} {:?}", key, bag.levels[key].name);
+        }
+    }
+    Ok(())
+}
+
+/// print the levels of a campaign, with where they're found, their
+/// signature, and whether the player won them
+pub fn info(icc: &InfoCampaignCommand) -> Result<()> {
+    let mut bag = read_campaign_bag(&icc.path)?;
+    let campaign = bag.campaigns.remove(0);
+    let win_file = WinFile::load().ok();
+    println!("campaign {:?}", campaign.name);
+    if !campaign.description.is_empty() {
+        println!("    {}", campaign.description);
+    }
+    println!("all levels playable: {}", if campaign.allow_all_levels { "yes" } else { "no" });
+    if let Some(wf) = &win_file {
+        println!("wins of player {:?}", wf.current_profile());
+    }
+    for (i, key) in campaign.levels.iter().enumerate() {
+        let found = find_level(&icc.path, &bag, key, LoadOrigin::Bag)?;
+        let origin = found.as_ref().map(|(origin, _)| *origin);
+        let (name, signature, won) = match found {
+            Some((_, level)) => {
+                let signature = Signature::new(&level)?;
+                let won = match win_file.as_ref().and_then(|wf| wf.record(&signature)) {
+                    Some(record) => match record.best {
+                        Some(best) => format!("WON ({})", best),
+                        None => "WON".to_string(),
+                    },
+                    None => String::new(),
+                };
+                (level.name, signature.as_str().to_string(), won)
+            }
+            None => (String::new(), "-".to_string(), String::new()),
+        };
+        println!(
+            "{:>3}. {:<20} {:<30} {:<7} {} {}",
+            i + 1,
+            key,
+            format!("{:?}", name),
+            origin_label(origin),
+            signature,
+            won,
+        );
+    }
+    Ok(())
+}
+
+/// check that all the levels referenced by the campaign can be
+/// found, either in the bag or in their own files
+pub fn check(ccc: &CheckCampaignCommand) -> Result<()> {
+    let bag = read_campaign_bag(&ccc.path)?;
+    let mut nb_missing = 0;
+    for campaign in &bag.campaigns {
+        for key in &campaign.levels {
+            match find_level(&ccc.path, &bag, key, LoadOrigin::Bag) {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    nb_missing += 1;
+                    println!("{:?}: not found", key);
+                }
+                Err(e) => {
+                    nb_missing += 1;
+                    println!("{:?}: {}", key, e);
+                }
+            }
+        }
+    }
+    if nb_missing > 0 {
+        return Err(anyhow!("{} level(s) can't be loaded", nb_missing));
+    }
+    println!("all levels found");
+    Ok(())
+}
@@ -6,10 +6,16 @@ use {
             Level,
             Bag,
         },
-        win_db,
+        win_db::{
+            self,
+            Score,
+        },
     },
     std::{
-        path::Path,
+        path::{
+            Path,
+            PathBuf,
+        },
     },
     super::*,
 };
@@ -24,7 +30,7 @@ pub struct LoadedCampaign {
     pub levels: Vec<LoadedLevel>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LoadOrigin {
 
     /// the level comes from the same file than the campaign
@@ -38,26 +44,61 @@ pub enum LoadOrigin {
 pub struct LoadedLevel {
     pub level: Level,
     pub won: bool,
+    pub stars: usize, // 0 when not won
+    pub best: Option<Score>, // best performances of the player
+    pub par: Option<Score>,  // best performances of all players
 }
 
-fn load_external_level(
+/// find the file of a level referenced by the campaign, next
+/// to the campaign file, and load it
+pub fn find_external_level(
     campaign_path: &Path,
     level_key: &str,
-) -> Result<Option<Level>> {
+) -> Result<Option<(PathBuf, Level)>> {
     debug!("looking for level {:?} in {:?}", level_key, campaign_path);
     for sf in persist::FORMATS {
-        let path = campaign_path.with_file_name(level_key).with_extension(sf.key());
+        let path = campaign_path.with_file_name(format!("{}.{}", level_key, sf.extension()));
         debug!("trying path {:?}", &path);
         if path.exists() {
-            let mut bag: Bag = persist::read_file(&path)?;
+            let mut bag: Bag = persist::read_bag_file(&path)?;
             if let Some(level) = bag.as_sole_level() {
-                return Ok(Some(level));
+                return Ok(Some((path, level)));
             }
         }
     }
     Ok(None)
 }
 
+fn load_external_level(
+    campaign_path: &Path,
+    level_key: &str,
+) -> Result<Option<Level>> {
+    Ok(find_external_level(campaign_path, level_key)?.map(|(_, level)| level))
+}
+
+/// find a level referenced by the campaign, either in the bag or in
+/// its own file, looking first in the preferred origin
+pub fn find_level(
+    campaign_path: &Path,
+    bag: &Bag,
+    level_key: &str,
+    preferred_origin: LoadOrigin,
+) -> Result<Option<(LoadOrigin, Level)>> {
+    let in_bag = || bag.levels.get(level_key)
+        .map(|level| (LoadOrigin::Bag, level.clone()));
+    Ok(match preferred_origin {
+        LoadOrigin::Bag => match in_bag() {
+            Some(found) => Some(found),
+            None => load_external_level(campaign_path, level_key)?
+                .map(|level| (LoadOrigin::External, level)),
+        }
+        LoadOrigin::External => match load_external_level(campaign_path, level_key)? {
+            Some(level) => Some((LoadOrigin::External, level)),
+            None => in_bag(),
+        }
+    })
+}
+
 impl LoadedCampaign {
 
     /// load the levels
@@ -73,31 +114,17 @@ impl LoadedCampaign {
         let campaign = bag.campaigns.pop().expect("tried to load a bag without campaign");
         let mut levels = Vec::new();
         for key in &campaign.levels {
-            let level = match preferred_origin {
-                LoadOrigin::Bag => match bag.levels.remove(key) {
-                    Some(level) => level,
-                    None => load_external_level(path, key)?
-                        .ok_or(anyhow!("Level {:?} not found", key))?,
-                }
-                LoadOrigin::External => match load_external_level(path, key)? {
-                    Some(level) => level,
-                    None => bag.levels.remove(key)
-                        .ok_or(anyhow!("Level {:?} not found", key))?,
-                }
-            };
+            let (_, level) = find_level(path, &bag, key, preferred_origin)?
+                .ok_or_else(|| anyhow!("Level {:?} not found", key))?;
             levels.push(LoadedLevel {
                 won: false,
+                stars: 0,
+                best: None,
+                par: None,
                 level,
             });
         }
-        if levels.is_empty() {
-            Err(anyhow!("Empty campaign"))
-        } else {
-            Ok(Self {
-                campaign,
-                levels,
-            })
-        }
+        Self::new(campaign, levels)
     }
 
     /// build a loaded campaign assuming the bag already
@@ -110,17 +137,23 @@ impl LoadedCampaign {
                 .ok_or(anyhow!("Level {:?} not in bag", key))?;
             levels.push(LoadedLevel {
                 won: false,
+                stars: 0,
+                best: None,
+                par: None,
                 level,
             });
         }
+        Self::new(campaign, levels)
+    }
+
+    fn new(campaign: Campaign, levels: Vec<LoadedLevel>) -> Result<Self> {
         if levels.is_empty() {
-            Err(anyhow!("Empty campaign"))
-        } else {
-            Ok(Self {
-                campaign,
-                levels,
-            })
+            return Err(anyhow!("Empty campaign"));
         }
+        Ok(Self {
+            campaign,
+            levels,
+        })
     }
 
     /// check in the win database to determine what levels have been won.
@@ -132,11 +165,19 @@ impl LoadedCampaign {
             for level in self.levels.iter_mut() {
                 let signature = win_db::Signature::new(&level.level)?;
                 level.won = win_file.has_win(&signature);
+                level.stars = win_file.stars(&signature);
+                level.best = win_file.best(&signature);
+                level.par = win_file.par(&signature);
             }
         }
         Ok(())
     }
 
+    /// the stars won on all levels of the campaign
+    pub fn total_stars(&self) -> usize {
+        self.levels.iter().map(|ll| ll.stars).sum()
+    }
+
     pub fn name(&self) -> &str {
         &self.campaign.name
     }
@@ -5,20 +5,36 @@ use {
         app::{
             NewCampaignCommand,
             PackCampaignCommand,
+            UnpackCampaignCommand,
+        },
+        core::{
+            Board,
+            Ruleset,
         },
         persist::{
             self,
             Bag,
+            Level,
             SerdeFormat,
         },
+        validate,
+    },
+    std::{
+        fs,
+        path::Path,
     },
 };
 
 mod campaign;
+mod inspect;
 mod loaded_campaign;
 
 pub use {
-    campaign::Campaign,
+    campaign::{
+        Campaign,
+        check_level_key,
+    },
+    inspect::*,
     loaded_campaign::*,
 };
 
@@ -29,7 +45,7 @@ pub fn create(ncc: &NewCampaignCommand) -> Result<()> {
     let format = ncc.output_format.as_ref()
         .and_then(|key| SerdeFormat::from_key(&key))
         .unwrap_or(SerdeFormat::Json);
-    persist::write_file(
+    persist::write_bag_file(
         &bag,
         &ncc.path,
         Some(format),
@@ -37,11 +53,30 @@ pub fn create(ncc: &NewCampaignCommand) -> Result<()> {
     )
 }
 
+/// read the level of a level file, or all the levels
+/// of a campaign file, each one with the ruleset of its
+/// campaign, to apply to its board
+pub fn read_levels(path: &Path) -> Result<Vec<(Level, Option<Ruleset>)>> {
+    let mut bag: Bag = persist::read_bag_file(path)?;
+    if let Some(level) = bag.as_sole_level() {
+        Ok(vec![(level, None)])
+    } else if bag.is_campaign() {
+        let loaded_campaign = LoadedCampaign::load(path, bag, LoadOrigin::External)?;
+        let ruleset = loaded_campaign.campaign.ruleset;
+        Ok(loaded_campaign.levels
+            .into_iter()
+            .map(|ll| (ll.level, ruleset.clone()))
+            .collect())
+    } else {
+        Err(anyhow!("nothing found in bag"))
+    }
+}
+
 /// write a bag containing a campaign and its levels
 /// (which are found preferably in external files and
 /// in the initial bag if not found externally)
 pub fn pack(pcc: &PackCampaignCommand) -> Result<()> {
-    let in_bag: Bag = persist::read_file(&pcc.unpacked_path)?;
+    let in_bag: Bag = persist::read_bag_file(&pcc.unpacked_path)?;
     if !in_bag.is_campaign() {
         Err(anyhow!("no campaign found in bag"))?
     }
@@ -50,6 +85,25 @@ pub fn pack(pcc: &PackCampaignCommand) -> Result<()> {
         in_bag,
         LoadOrigin::External,
     )?;
+    let mut nb_invalid = 0;
+    for (key, ll) in loaded_campaign.campaign.levels.iter().zip(&loaded_campaign.levels) {
+        let mut board = Board::from(&ll.level);
+        board.apply_campaign_ruleset(&ll.level, loaded_campaign.campaign.ruleset.as_ref());
+        let issues = validate::validate(&board);
+        if !issues.is_empty() {
+            nb_invalid += 1;
+            println!("{:?}:", key);
+            for issue in &issues {
+                println!("    {}", issue);
+            }
+        }
+    }
+    if nb_invalid > 0 && !pcc.force {
+        return Err(anyhow!(
+            "{} level(s) with issues, use --force to pack them anyway",
+            nb_invalid,
+        ));
+    }
     let mut out_bag = Bag::from(loaded_campaign.campaign.clone());
     let len = loaded_campaign.campaign.levels.len();
     for i in 0..len {
@@ -58,10 +112,60 @@ pub fn pack(pcc: &PackCampaignCommand) -> Result<()> {
             loaded_campaign.levels[i].level.clone(),
         );
     }
-    persist::write_file(
+    persist::write_bag_file(
         &out_bag,
         &pcc.packed_path,
         None,
         false,
     )
 }
+
+/// write each level of a campaign into its own file, named
+/// by its key, and the campaign (without its levels) next to them
+pub fn unpack(ucc: &UnpackCampaignCommand) -> Result<()> {
+    let mut in_bag: Bag = persist::read_bag_file(&ucc.packed_path)?;
+    if !in_bag.is_campaign() {
+        Err(anyhow!("no campaign found in bag"))?
+    }
+    let format = ucc.output_format.as_ref()
+        .and_then(|key| SerdeFormat::from_key(key))
+        .unwrap_or(SerdeFormat::Json);
+    let campaign = in_bag.campaigns.remove(0);
+    let stem = ucc.packed_path.file_stem()
+        .ok_or_else(|| anyhow!("invalid campaign path"))?;
+    let campaign_path = ucc.dir.join(format!(
+        "{}.{}",
+        stem.to_string_lossy(),
+        SerdeFormat::Json.extension(),
+    ));
+    // the levels are all found, and their paths checked,
+    // before anything is written
+    let mut files = Vec::new();
+    for key in &campaign.levels {
+        check_level_key(key)
+            .map_err(|e| anyhow!("Invalid level key {:?}: {}", key, e))?;
+        let (_, level) = find_level(&ucc.packed_path, &in_bag, key, LoadOrigin::Bag)?
+            .ok_or_else(|| anyhow!("Level {:?} not found", key))?;
+        let path = ucc.dir.join(format!("{}.{}", key, format.extension()));
+        if path == campaign_path || files.iter().any(|(p, _)| p == &path) {
+            return Err(anyhow!("Two files would be written at {:?}", path));
+        }
+        files.push((path, level));
+    }
+    if !ucc.force {
+        let existing = files.iter()
+            .map(|(path, _)| path)
+            .chain(std::iter::once(&campaign_path))
+            .find(|path| path.exists());
+        if let Some(path) = existing {
+            return Err(anyhow!("{:?} already exists, use --force to overwrite it", path));
+        }
+    }
+    fs::create_dir_all(&ucc.dir)?;
+    for (path, level) in files {
+        println!("writing {:?}", &path);
+        persist::write_bag_file(&Bag::from(level), &path, Some(format), true)?;
+    }
+    println!("writing {:?}", &campaign_path);
+    persist::write_file(&Bag::from(campaign), &campaign_path, Some(SerdeFormat::Json), true)
+}
@@ -0,0 +1,17 @@
+use {
+    crate::{
+        display::Layout,
+    },
+};
+
+mod state;
+
+pub use state::CampaignEditor;
+
+pub static LABEL: &str = "campaign editor";
+
+pub const LAYOUT: Layout = Layout {
+    header_height: 0,
+    pen_panel_height: 0,
+    status_height: 1,
+};
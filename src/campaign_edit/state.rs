@@ -0,0 +1,565 @@
+use {
+    anyhow::Result,
+    crate::{
+        app::{
+            Context,
+            State,
+            StateTransition,
+        },
+        campaign::{
+            self,
+            Campaign,
+            LoadOrigin,
+        },
+        core::Ruleset,
+        display::{
+            mad_skin,
+            Screen,
+            Status,
+        },
+        persist::{
+            self,
+            Bag,
+            Level,
+            SerdeFormat,
+        },
+    },
+    crossterm::{
+        event::{
+            KeyCode,
+            KeyEvent,
+            KeyModifiers,
+        },
+    },
+    fnv::FnvHashMap,
+    std::{
+        fs,
+        io::Write,
+        path::{
+            Path,
+            PathBuf,
+        },
+    },
+    super::*,
+    termimad::{
+        Area,
+        Event,
+        InputField,
+        TextView,
+    },
+};
+
+const DEFAULT_STATUS: &str = "*↑*/*↓* to select, *ctrl*+*↑*/*↓* to move, *a* to add, *r* to rename, *x* to remove, *enter* to edit the level, *n*/*d*/*l* for name, description and unlocking, *s* to save, *q* to quit";
+
+/// what is being typed in the input field of the status line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Description,
+    NewKey, // key of a level to add after the selection
+    Key,    // new key of the selected level
+}
+
+impl Field {
+    fn prompt(self) -> &'static str {
+        match self {
+            Self::Name => "Campaign name:",
+            Self::Description => "Description:",
+            Self::NewKey => "Key of the new level:",
+            Self::Key => "New key:",
+        }
+    }
+}
+
+/// what's known of a level referenced by the campaign
+struct LevelEntry {
+    origin: Option<LoadOrigin>, // none when the level isn't found
+    level_name: Option<String>,
+}
+
+/// A screen to edit a campaign file: its name, description,
+/// and the list of its levels.
+pub struct CampaignEditor {
+    path: PathBuf,
+    campaign: Campaign,
+    bag_levels: FnvHashMap<String, Level>, // levels in the campaign file
+    entries: Vec<LevelEntry>, // one per key of campaign.levels
+    selection: usize,
+    scroll: usize,
+    area_height: usize,
+    status: Status,
+    input: Option<(Field, InputField)>,
+    modified: bool, // are there unsaved changes
+    quit_warned: bool, // was the user warned of unsaved changes on q, since the last change
+}
+
+impl CampaignEditor {
+
+    /// open the campaign of a file which is known to contain one
+    pub fn new(path: &Path, mut bag: Bag) -> Result<Self> {
+        let campaign = bag.as_sole_campaign()
+            .ok_or_else(|| anyhow!("no campaign found in bag"))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            campaign,
+            bag_levels: bag.levels,
+            entries: Vec::new(), // filled at each run
+            selection: 0,
+            scroll: 0,
+            area_height: 0,
+            status: Status::from_message(DEFAULT_STATUS.to_string()),
+            input: None,
+            modified: false,
+            quit_warned: false,
+        })
+    }
+
+    /// look for the level with this key, externally then in the bag
+    fn entry(&self, key: &str) -> LevelEntry {
+        match campaign::find_external_level(&self.path, key) {
+            Ok(Some((_, level))) => {
+                return LevelEntry {
+                    origin: Some(LoadOrigin::External),
+                    level_name: Some(level.name),
+                };
+            }
+            Err(e) => {
+                warn!("error while reading level {:?}: {:?}", key, e);
+            }
+            _ => {}
+        }
+        match self.bag_levels.get(key) {
+            Some(level) => LevelEntry {
+                origin: Some(LoadOrigin::Bag),
+                level_name: Some(level.name.clone()),
+            },
+            None => LevelEntry {
+                origin: None,
+                level_name: None,
+            },
+        }
+    }
+
+    /// check again where the levels are, as they may have been
+    /// changed in the level editor
+    fn refresh_entries(&mut self) {
+        self.entries = self.campaign.levels.iter()
+            .map(|key| self.entry(key))
+            .collect();
+        if self.selection >= self.entries.len() {
+            self.selection = self.entries.len().max(1) - 1;
+        }
+    }
+
+    /// the path of the file of the level, which may not exist yet
+    fn level_path(&self, key: &str) -> PathBuf {
+        if let Ok(Some((path, _))) = campaign::find_external_level(&self.path, key) {
+            return path;
+        }
+        let format = SerdeFormat::from_path(&self.path).unwrap_or(SerdeFormat::Json);
+        self.path.with_file_name(format!("{}.{}", key, format.extension()))
+    }
+
+    fn selected_key(&self) -> Option<&String> {
+        self.campaign.levels.get(self.selection)
+    }
+
+    fn save(&mut self) -> Result<()> {
+        let mut bag = Bag::from(self.campaign.clone());
+        // levels which aren't referenced anymore aren't kept
+        for key in &self.campaign.levels {
+            if let Some(level) = self.bag_levels.get(key) {
+                bag.levels.insert(key.to_string(), level.clone());
+            }
+        }
+        persist::write_bag_file(&bag, &self.path, None, true)?;
+        self.modified = false;
+        Ok(())
+    }
+
+    fn set_modified(&mut self) {
+        self.modified = true;
+        self.quit_warned = false;
+    }
+
+    fn check_new_key(&self, key: &str) -> Result<()> {
+        campaign::check_level_key(key)?;
+        if self.campaign.levels.iter().any(|k| k == key) {
+            Err(anyhow!("There's already a level with this key"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn add_level(&mut self, key: String) -> Result<()> {
+        self.check_new_key(&key)?;
+        let idx = if self.campaign.levels.is_empty() { 0 } else { self.selection + 1 };
+        let entry = self.entry(&key);
+        self.campaign.levels.insert(idx, key);
+        self.entries.insert(idx, entry);
+        self.selection = idx;
+        self.set_modified();
+        Ok(())
+    }
+
+    /// change the key of the selected level, renaming its file
+    /// (and then saving the campaign) or moving it in the bag
+    fn rename_level(&mut self, new_key: String) -> Result<()> {
+        let old_key = match self.selected_key() {
+            Some(key) if *key == new_key => return Ok(()),
+            Some(key) => key.clone(),
+            None => return Ok(()),
+        };
+        self.check_new_key(&new_key)?;
+        let mut file_renamed = false;
+        if let Some((old_path, _)) = campaign::find_external_level(&self.path, &old_key)? {
+            let new_path = match SerdeFormat::from_path(&old_path) {
+                Some(format) => self.path.with_file_name(format!("{}.{}", new_key, format.extension())),
+                None => self.path.with_file_name(&new_key),
+            };
+            if new_path.exists() {
+                return Err(anyhow!("File {:?} already exists", new_path));
+            }
+            fs::rename(&old_path, &new_path)?;
+            file_renamed = true;
+        }
+        if let Some(level) = self.bag_levels.remove(&old_key) {
+            self.bag_levels.insert(new_key.clone(), level);
+        }
+        self.campaign.levels[self.selection] = new_key;
+        self.entries[self.selection] = self.entry(&self.campaign.levels[self.selection]);
+        self.set_modified();
+        if file_renamed {
+            // the campaign must reference the new file even if
+            // the user quits without saving
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn remove_level(&mut self) {
+        if self.selection < self.campaign.levels.len() {
+            self.campaign.levels.remove(self.selection);
+            self.entries.remove(self.selection);
+            if self.selection > 0 && self.selection >= self.campaign.levels.len() {
+                self.selection -= 1;
+            }
+            self.set_modified();
+        }
+    }
+
+    /// move the selected level up (when `up`) or down in the list
+    fn move_level(&mut self, up: bool) {
+        let other = if up {
+            if self.selection == 0 {
+                return;
+            }
+            self.selection - 1
+        } else {
+            if self.selection + 1 >= self.campaign.levels.len() {
+                return;
+            }
+            self.selection + 1
+        };
+        self.campaign.levels.swap(self.selection, other);
+        self.entries.swap(self.selection, other);
+        self.selection = other;
+        self.set_modified();
+    }
+
+    fn select(&mut self, idx: usize) {
+        self.selection = idx;
+        if self.selection < self.scroll + 3 {
+            self.scroll = self.selection.saturating_sub(3);
+        } else if self.selection + 8 >= self.scroll + self.area_height {
+            self.scroll = (self.selection + 8).saturating_sub(self.area_height);
+        }
+    }
+
+    fn open_input(&mut self, field: Field) {
+        let content = match field {
+            Field::Name => self.campaign.name.clone(),
+            Field::Description => self.campaign.description.clone(),
+            Field::NewKey => String::new(),
+            Field::Key => match self.selected_key() {
+                Some(key) => key.clone(),
+                None => return,
+            },
+        };
+        let mut input_field = InputField::new(Area::uninitialized());
+        input_field.set_content(&content);
+        self.input = Some((field, input_field));
+    }
+
+    /// apply the content of the input field
+    fn close_input(&mut self) -> Result<()> {
+        let (field, input_field) = match self.input.take() {
+            Some(input) => input,
+            None => return Ok(()),
+        };
+        let content = input_field.get_content();
+        match field {
+            Field::Name => {
+                self.campaign.name = content;
+                self.set_modified();
+            }
+            Field::Description => {
+                self.campaign.description = content;
+                self.set_modified();
+            }
+            Field::NewKey => {
+                self.add_level(content.trim().to_string())?;
+            }
+            Field::Key => {
+                self.rename_level(content.trim().to_string())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn markdown(&self) -> Result<String> {
+        use std::fmt::Write;
+        let mut md = String::new();
+        write!(
+            md,
+            "\n# {}{}\n",
+            if self.campaign.name.is_empty() { "-unnamed campaign-" } else { &self.campaign.name },
+            if self.modified { " *(modified)*" } else { "" },
+        )?;
+        if !self.campaign.description.is_empty() {
+            write!(md, "\n{}\n", self.campaign.description)?;
+        }
+        write!(
+            md,
+            "\n{}\n",
+            if self.campaign.allow_all_levels {
+                "All levels can be played from the start"
+            } else {
+                "A level must be won to unlock the next one"
+            },
+        )?;
+        if self.campaign.levels.is_empty() {
+            write!(md, "\n*no level yet: hit* a *to add one*")?;
+        }
+        for (i, (key, entry)) in self.campaign.levels.iter().zip(&self.entries).enumerate() {
+            write!(
+                md,
+                "\n{}{}. **{}** {} `{}`",
+                if i == self.selection { "### " } else { "" },
+                i + 1,
+                key,
+                entry.level_name.as_deref().unwrap_or("-"),
+                match entry.origin {
+                    Some(LoadOrigin::External) => "file",
+                    Some(LoadOrigin::Bag) => "bag",
+                    None => "missing",
+                },
+            )?;
+        }
+        Ok(md)
+    }
+
+    fn write_status(
+        &mut self,
+        con: &mut Context,
+        screen: &Screen,
+    ) -> Result<()> {
+        if let Some((field, input_field)) = &mut self.input {
+            let prompt = field.prompt();
+            Status::from_message(prompt.to_string()).display(con, screen)?;
+            let area = &screen.areas.status;
+            let left = area.left + prompt.chars().count() as u16 + 2;
+            input_field.change_area(left, area.top, area.width.saturating_sub(left + 1));
+            input_field.display_on(con.w)?;
+            Ok(())
+        } else {
+            self.status.display(con, screen)
+        }
+    }
+
+    /// handle a key when the input field is open. Return true
+    /// if the event was handled
+    fn handle_input_key(
+        &mut self,
+        code: KeyCode,
+    ) -> bool {
+        if let Some((_, input_field)) = &mut self.input {
+            if input_field.apply_keycode_event(code) {
+                return true;
+            }
+        } else {
+            return false;
+        }
+        match code {
+            KeyCode::Esc => {
+                self.input = None;
+            }
+            KeyCode::Enter => {
+                self.status = match self.close_input() {
+                    Ok(()) => Status::from_message(DEFAULT_STATUS.to_string()),
+                    Err(e) => Status::from_error(e.to_string()),
+                };
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn handle_key_event(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<StateTransition> {
+        if self.handle_input_key(code) {
+            return None;
+        }
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        let mut status = Status::from_message(DEFAULT_STATUS.to_string());
+        let transition = match code {
+            KeyCode::Up if ctrl => {
+                self.move_level(true);
+                None
+            }
+            KeyCode::Down if ctrl => {
+                self.move_level(false);
+                None
+            }
+            KeyCode::Up if self.selection > 0 => {
+                self.select(self.selection - 1);
+                None
+            }
+            KeyCode::Down if self.selection + 1 < self.campaign.levels.len() => {
+                self.select(self.selection + 1);
+                None
+            }
+            KeyCode::Enter if self.selection < self.campaign.levels.len() => {
+                Some(StateTransition::EditLevel {
+                    level_idx: self.selection,
+                })
+            }
+            KeyCode::Delete | KeyCode::Char('x') => {
+                self.remove_level();
+                None
+            }
+            KeyCode::Char('a') => {
+                self.open_input(Field::NewKey);
+                None
+            }
+            KeyCode::Char('r') => {
+                self.open_input(Field::Key);
+                None
+            }
+            KeyCode::Char('n') => {
+                self.open_input(Field::Name);
+                None
+            }
+            KeyCode::Char('d') => {
+                self.open_input(Field::Description);
+                None
+            }
+            KeyCode::Char('l') => {
+                self.campaign.allow_all_levels = !self.campaign.allow_all_levels;
+                self.set_modified();
+                None
+            }
+            KeyCode::Char('s') => {
+                status = match self.save() {
+                    Ok(()) => Status::from_message("campaign saved".to_string()),
+                    Err(e) => {
+                        warn!("error while saving campaign: {:?}", e);
+                        Status::from_error("campaign saving failed".to_string())
+                    }
+                };
+                None
+            }
+            KeyCode::Char('?') => Some(StateTransition::Help),
+            KeyCode::Char('q') if self.modified && !self.quit_warned => {
+                self.quit_warned = true; // so that a second q quits
+                status = Status::from_error(
+                    "There are unsaved changes: hit *s* to save or *q* to quit anyway".to_string()
+                );
+                None
+            }
+            KeyCode::Char('q') => Some(StateTransition::Back),
+            _ => None,
+        };
+        self.status = status;
+        transition
+    }
+}
+
+impl State for CampaignEditor {
+
+    fn label(&self) -> &'static str {
+        LABEL
+    }
+
+    fn run(
+        &mut self,
+        con: &mut Context,
+    ) -> Result<StateTransition> {
+        let mut screen = Screen::new(LAYOUT);
+        let skin = mad_skin::make(&con.skin);
+        // levels may have been created, or changed, in the level editor
+        self.refresh_entries();
+        loop {
+            self.write_status(con, &screen)?;
+            let md = self.markdown()?;
+            self.area_height = screen.areas.board.height as usize;
+            let text = skin.area_text(&md, &screen.areas.board);
+            let mut text_view = TextView::from(
+                &screen.areas.board,
+                &text,
+            );
+            text_view.set_scroll(self.scroll as i32);
+            text_view.write_on(con.w)?;
+            con.w.flush()?;
+            let event = con.dam.next_event().unwrap();
+            con.dam.unblock();
+            match event {
+                Event::Key(KeyEvent { code, modifiers }) => {
+                    if let Some(next_state) = self.handle_key_event(code, modifiers) {
+                        return Ok(next_state);
+                    }
+                }
+                Event::Resize(width, height) => {
+                    screen.set_terminal_size(width, height);
+                }
+                _ => {
+                    debug!("ignored event: {:?}", event);
+                }
+            }
+        }
+    }
+
+    fn get_level(
+        &self,
+        level_idx: usize,
+    ) -> Option<Level> {
+        let key = self.campaign.levels.get(level_idx)?;
+        match campaign::find_external_level(&self.path, key) {
+            Ok(Some((_, level))) => Some(level),
+            _ => Some(self.bag_levels.get(key).cloned().unwrap_or_else(|| {
+                Level {
+                    name: key.to_string(),
+                    ..Level::default()
+                }
+            })),
+        }
+    }
+
+    fn get_level_path(
+        &self,
+        level_idx: usize,
+    ) -> Option<PathBuf> {
+        self.campaign.levels.get(level_idx)
+            .map(|key| self.level_path(key))
+    }
+
+    fn get_ruleset(
+        &self,
+        _level_idx: usize,
+    ) -> Option<Ruleset> {
+        self.campaign.ruleset.clone()
+    }
+}
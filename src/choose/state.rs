@@ -7,13 +7,26 @@ use {
             StateTransition,
         },
         campaign::LoadedCampaign,
+        core::{
+            Ruleset,
+            star_marks,
+        },
         display::{
             mad_skin,
             Screen,
             Status,
         },
+        generate::{
+            Generator,
+            GeneratorParams,
+        },
         persist::Level,
     },
+    crossbeam::channel::{
+        unbounded,
+        Receiver,
+        TryRecvError,
+    },
     crossterm::{
         event::{
             KeyCode,
@@ -22,6 +35,11 @@ use {
     },
     std::{
         io::Write,
+        thread,
+        time::{
+            Duration,
+            SystemTime,
+        },
     },
     super::*,
     termimad::{
@@ -30,6 +48,8 @@ use {
     },
 };
 
+const DEFAULT_STATUS: &str = "Hit *↓* and *↑* to change the selection, *enter* to open it, *q* to quit";
+
 /// A screen letting the user choose a level.
 ///
 /// The current implementation is based on a raw text_view.
@@ -42,15 +62,15 @@ pub struct ChooseLevelState {
     nb_playable_levels: usize,
     scroll: usize,
     area_height: usize,
+    random_level: Option<(Level, usize)>, // the last generated one, and its seed
+    generation: Option<Receiver<Result<(Level, usize)>>>, // the random level being generated, and its seed
 }
 
 impl ChooseLevelState {
     pub fn new(
         loaded_campaign: LoadedCampaign,
     ) -> Result<Self> {
-        let status = Status::from_message(
-            "Hit *↓* and *↑* to change the selection, *enter* to open it, *q* to quit".to_string()
-        );
+        let status = Status::from_message(DEFAULT_STATUS.to_string());
         let nb_playable_levels = 0; // will be updated on check wins
         let area_height = 0; // will be updated on first draw
         Ok(Self {
@@ -60,13 +80,78 @@ impl ChooseLevelState {
             nb_playable_levels,
             scroll: 0,
             area_height,
+            random_level: None,
+            generation: None,
         })
     }
 
+    /// the index of the "random level" entry, after the
+    /// levels of the campaign
+    fn random_idx(&self) -> usize {
+        self.loaded_campaign.levels.len()
+    }
+
+    /// start generating a new random level in another thread,
+    /// with a seed depending on the time.
+    ///
+    /// The level is only checked to be winnable with this seed, so
+    /// the world plays with it too. The checks are kept short so
+    /// that the user doesn't wait too long.
+    fn start_generation(&mut self) {
+        let seed = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as usize);
+        let (tx, rx) = unbounded();
+        thread::spawn(move || {
+            let generator = Generator {
+                max_attempts: 8,
+                max_solve_duration: Duration::from_secs(1),
+                ..Generator::new(seed, GeneratorParams::default())
+            };
+            let _ = tx.send(generator.generate().map(|level| (level, seed)));
+        });
+        self.generation = Some(rx);
+        self.status = Status::from_message(
+            "Generating a random level... Hit *esc* to cancel".to_string()
+        );
+    }
+
+    /// check whether the random level being generated is ready
+    fn check_generation(&mut self) -> Option<StateTransition> {
+        let result = match self.generation.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => {
+                return None;
+            }
+            Err(TryRecvError::Disconnected) => Err(anyhow!("generation thread died")),
+        };
+        self.generation = None;
+        match result {
+            Ok(random_level) => {
+                self.random_level = Some(random_level);
+                Some(StateTransition::PlayLevel {
+                    level_idx: self.random_idx(),
+                })
+            }
+            Err(e) => {
+                warn!("level generation failed: {:?}", e);
+                self.status = Status::from_error("No level could be generated".to_string());
+                None
+            }
+        }
+    }
+
     fn markdown(&self) -> Result<String> {
         use std::fmt::Write;
         let mut md = String::new();
         write!(md, "\n# {}\n", self.loaded_campaign.name())?;
+        let description = &self.loaded_campaign.campaign.description;
+        if !description.trim().is_empty() {
+            write!(md, "\n{}\n", description.trim())?;
+        }
+        if self.loaded_campaign.campaign.stars_per_level.is_some() {
+            write!(md, "\n{} ★\n", self.loaded_campaign.total_stars())?;
+        }
         for (i, level) in self.loaded_campaign.levels.iter().enumerate() {
             write!(
                 md,
@@ -81,7 +166,36 @@ impl ChooseLevelState {
                 level.level.name,
                 if level.won { " ` WON `" } else { "" },
             )?;
+            if level.won {
+                write!(md, " {}", star_marks(level.stars))?;
+            }
+            if let Some(best) = level.best {
+                write!(md, " *{} moves*", best.moves)?;
+            }
+            if i == self.selection && !level.level.objectives.is_empty() {
+                let objectives: Vec<String> = level.level.objectives.iter()
+                    .map(|o| o.to_string())
+                    .collect();
+                write!(md, "\n\nobjectives: {}", objectives.join(", "))?;
+            }
+            if i == self.selection {
+                if let Some(best) = level.best {
+                    write!(md, "\n\nyour best: {}", best)?;
+                }
+                if let Some(par) = level.par {
+                    write!(md, "\n\npar: {}", par)?;
+                }
+            }
+            // the intro of the selected level is shown below it
+            if i == self.selection && i < self.nb_playable_levels && !level.level.intro.trim().is_empty() {
+                write!(md, "\n\n{}\n", level.level.intro.trim())?;
+            }
         }
+        write!(
+            md,
+            "\n\n{}*a random level*",
+            if self.selection == self.random_idx() { "### " } else { "" },
+        )?;
         Ok(md)
     }
 
@@ -90,6 +204,18 @@ impl ChooseLevelState {
         code: KeyCode,
     ) -> Result<Option<StateTransition>> {
         Ok(match code {
+            KeyCode::Esc if self.generation.is_some() => {
+                // the thread ends by itself, its level is just dropped
+                self.generation = None;
+                self.status = Status::from_message(DEFAULT_STATUS.to_string());
+                None
+            }
+            KeyCode::Char('q') | KeyCode::Char('Q') => Some(StateTransition::Quit),
+            _ if self.generation.is_some() => None,
+            KeyCode::Up if self.selection == self.random_idx() && self.nb_playable_levels > 0 => {
+                self.selection = self.nb_playable_levels - 1;
+                None
+            }
             KeyCode::Up if self.selection > 0 => {
                 self.selection -= 1;
                 if self.scroll > 0 && self.selection < self.scroll + 3 {
@@ -97,18 +223,28 @@ impl ChooseLevelState {
                 }
                 None
             }
-            KeyCode::Down if self.selection < self.nb_playable_levels - 1 => {
+            KeyCode::Down if self.selection + 1 < self.nb_playable_levels => {
                 self.selection += 1;
                 if self.selection + 8 >= self.scroll + self.area_height {
                     self.scroll += 1;
                 }
                 None
             }
+            KeyCode::Down if self.selection < self.random_idx() => {
+                self.selection = self.random_idx();
+                if self.selection + 8 >= self.scroll + self.area_height {
+                    self.scroll += 1;
+                }
+                None
+            }
+            KeyCode::Enter if self.selection == self.random_idx() => {
+                self.start_generation();
+                None
+            }
             KeyCode::Enter => Some(StateTransition::PlayLevel {
                 level_idx: self.selection,
             }),
             KeyCode::Char('?') => Some(StateTransition::Help),
-            KeyCode::Char('q') | KeyCode::Char('Q') => Some(StateTransition::Quit),
             _ => None,
         })
     }
@@ -127,8 +263,12 @@ impl ChooseLevelState {
         &mut self,
     ) -> Result<()> {
         self.loaded_campaign.check_wins()?;
+        let nb_levels = self.loaded_campaign.campaign.levels.len();
         self.nb_playable_levels = if self.loaded_campaign.campaign.allow_all_levels {
-            self.loaded_campaign.campaign.levels.len()
+            nb_levels
+        } else if let Some(stars_per_level) = self.loaded_campaign.campaign.stars_per_level {
+            let total = self.loaded_campaign.total_stars();
+            (total / stars_per_level.max(1) + 1).min(nb_levels)
         } else {
             let mut nb = 0;
             for level in &self.loaded_campaign.levels {
@@ -171,6 +311,12 @@ impl State for ChooseLevelState {
             text_view.set_scroll(self.scroll as i32);
             text_view.write_on(con.w)?;
             con.w.flush()?;
+            if self.generation.is_some() && con.dam.try_wait(Duration::from_millis(50)) {
+                if let Some(next_state) = self.check_generation() {
+                    return Ok(next_state);
+                }
+                continue;
+            }
             let event = con.dam.next_event().unwrap();
             con.dam.unblock();
             match event {
@@ -194,10 +340,43 @@ impl State for ChooseLevelState {
         &self,
         level_idx: usize,
     ) -> Option<Level> {
+        if level_idx == self.random_idx() {
+            return self.random_level.as_ref().map(|(level, _)| level.clone());
+        }
         self.loaded_campaign.levels
             .get(level_idx)
             .map(|ll| ll.level.clone())
     }
 
+    fn get_ruleset(
+        &self,
+        level_idx: usize,
+    ) -> Option<Ruleset> {
+        if level_idx == self.random_idx() {
+            return None;
+        }
+        self.loaded_campaign.campaign.ruleset.clone()
+    }
+
+    /// the random level is new each time, so there's no
+    /// point in keeping its wins
+    fn records_games(
+        &self,
+        level_idx: usize,
+    ) -> bool {
+        level_idx != self.random_idx()
+    }
+
+    fn get_seed(
+        &self,
+        level_idx: usize,
+    ) -> Option<usize> {
+        if level_idx == self.random_idx() {
+            self.random_level.as_ref().map(|&(_, seed)| seed)
+        } else {
+            None
+        }
+    }
+
 }
 
@@ -8,13 +8,12 @@ use {
         fmt,
         hash::Hash,
     },
-    super::*,
     termimad::{
         StyledChar,
     },
 };
 
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct ActorState {
 
     #[serde(default)]
@@ -59,30 +58,6 @@ pub static ACTORS: &[ActorKind] = &[
     ActorKind::Sheep,
     ActorKind::Dragon,
 ];
-pub static FOX_PREYS: &[ActorKind] = &[
-    ActorKind::Lapin,
-];
-pub static KNIGHT_PREYS: &[ActorKind] = &[
-    ActorKind::Wolf,
-    ActorKind::Fox,
-    ActorKind::Hunter,
-];
-pub static HUNTER_PREYS: &[ActorKind] = &[ // when not drunk
-    ActorKind::Lapin,
-    ActorKind::Wolf,
-    ActorKind::Fox,
-];
-pub static WOLF_PREYS: &[ActorKind] = &[
-    ActorKind::Lapin,
-    ActorKind::Hunter,
-];
-pub static DRAGON_PREYS: &[ActorKind] = &[
-    ActorKind::Lapin,
-    ActorKind::Hunter,
-    ActorKind::Wolf,
-    ActorKind::Fox,
-    ActorKind::Sheep,
-];
 impl ActorKind {
     pub fn drinks_wine(self) -> bool {
         match self {
@@ -90,40 +65,6 @@ impl ActorKind {
             _ => false,
         }
     }
-    pub fn eats(self, other: Self) -> bool {
-        use ActorKind::*;
-        match (self, other) {
-            (Fox, Lapin) => true,
-            (Knight, Fox) => true,
-            (Knight, Hunter) => true,
-            (Knight, Wolf) => true,
-            (Wolf, Hunter) => true,
-            (Wolf, Sheep) => true,
-            (Wolf, Lapin) => true,
-            (Dragon, _) => true,
-            _ => false,
-        }
-    }
-    pub fn runs_after(self, other: Self) -> bool {
-        use ActorKind::*;
-        match (self, other) {
-            (Fox, Lapin) => true,
-            (Hunter, Lapin) => true,
-            (Knight, Fox) => true,
-            (Wolf, Hunter) => true,
-            (Wolf, Sheep) => true,
-            (Wolf, Lapin) => true,
-            (Dragon, _) => true,
-            _ => false,
-        }
-    }
-    pub fn is_immune_to_fire(self, firer: ActorKind) -> bool {
-        match (self, firer) {
-            (_, Self::Dragon) => false,
-            (Self::Knight, _) => true,
-             _ => false,
-        }
-    }
     pub fn skin(self, skin: &Skin) -> &StyledChar {
         use ActorKind::*;
         match self {
@@ -138,7 +79,7 @@ impl ActorKind {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct Actor {
     pub kind: ActorKind,
 
@@ -155,52 +96,6 @@ impl Actor {
             state: ActorState::default(),
         }
     }
-    pub fn can_enter(self, terrain: Terrain) -> bool {
-        use {ActorKind::*, Terrain::*};
-        match (self.kind, terrain) {
-            (_, Mud) => true,
-            (_, Grass) => true,
-            (Knight, Sand) => false,
-            (_, Sand) => true,
-            (Dragon, Water) => true, // dragon flies over water
-            _ => false, // water and wall
-        }
-    }
-    pub fn preys(self) -> Option<&'static[ActorKind]> {
-        use ActorKind::*;
-        match self.kind {
-            Fox => Some(FOX_PREYS),
-            Knight => Some(KNIGHT_PREYS),
-            Hunter => Some(HUNTER_PREYS),
-            Wolf => Some(WOLF_PREYS),
-            Dragon => Some(DRAGON_PREYS),
-            _ => None,
-        }
-    }
-    pub fn eats(self, other: Actor) -> bool {
-        self.kind.eats(other.kind)
-    }
-    pub fn fires_on(self, other: Actor) -> bool {
-        use ActorKind::*;
-        match self.kind {
-            Dragon => true,
-            Hunter if self.state.drunk => true,
-            Hunter => match other.kind {
-                Fox | Knight | Lapin | Wolf => true,
-                _ => false,
-            }
-            _ => false,
-        }
-        // soon : https://github.com/rust-lang/rust/issues/54883
-        // match (self.kind, other.kind) {
-        //     (Hunter, _) if self.state.drunk => true,
-        //     (Hunter, Fox | Knight | Lapin | Wolf) => true,
-        //     _ => false,
-        // }
-    }
-    pub fn runs_after(self, other: Actor) -> bool {
-        self.kind.runs_after(other.kind)
-    }
     pub fn is_aiming(self) -> bool {
         self.state.aim.is_some()
     }
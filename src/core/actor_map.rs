@@ -151,6 +151,11 @@ impl ActorMap {
     pub fn ai_actor_ids(&self) -> Range<ActorId> {
         1..self.actors.len()
     }
+    /// replace all actors, keeping the allocated position map
+    pub fn reset(&mut self, actors: Vec<Actor>) {
+        self.actors = actors;
+        self.rebuild();
+    }
     fn rebuild(&mut self) {
         self.ref_pos_map.clear();
         for (i, a) in self.actors.iter().enumerate() {
@@ -0,0 +1,88 @@
+//! a compact text representation of terrains, actors and items,
+//! one char per cell, used by the text format of levels, the
+//! stamps, and the ascii boards of the tests
+
+use {
+    crate::pos::*,
+    super::*,
+};
+
+/// the terrain on which actors and items written in
+/// an ascii grid are
+pub const ASCII_UNDER_TERRAIN: Terrain = Terrain::Mud;
+
+pub fn terrain_to_char(terrain: Terrain) -> char {
+    match terrain {
+        Terrain::Mud => '.',
+        Terrain::Stone => '#',
+        Terrain::Grass => '"',
+        Terrain::Water => '~',
+        Terrain::Sand => ':',
+        Terrain::Ice => '-',
+        Terrain::Bridge => '=',
+        Terrain::Bush => '*',
+        Terrain::Hole => 'o',
+    }
+}
+
+pub fn terrain_from_char(c: char) -> Option<Terrain> {
+    TERRAINS.iter().find(|&&t| terrain_to_char(t) == c).copied()
+}
+
+pub fn actor_kind_to_char(kind: ActorKind) -> char {
+    match kind {
+        ActorKind::Lapin => 'L',
+        ActorKind::Knight => 'K',
+        ActorKind::Wolf => 'W',
+        ActorKind::Fox => 'F',
+        ActorKind::Hunter => 'H',
+        ActorKind::Sheep => 'S',
+        ActorKind::Dragon => 'D',
+    }
+}
+
+pub fn actor_kind_from_char(c: char) -> Option<ActorKind> {
+    ACTORS.iter().find(|&&k| actor_kind_to_char(k) == c).copied()
+}
+
+pub fn item_kind_to_char(kind: ItemKind) -> char {
+    match kind {
+        ItemKind::Carrot => 'c',
+        ItemKind::Wine => 'w',
+        ItemKind::Key => 'k',
+        ItemKind::Door => 'd',
+        ItemKind::Lever => 'l',
+    }
+}
+
+pub fn item_kind_from_char(c: char) -> Option<ItemKind> {
+    ITEMS.iter().find(|&&k| item_kind_to_char(k) == c).copied()
+}
+
+/// the lines of an ascii grid, without indentation nor empty lines
+pub(super) fn grid_lines(grid: &str) -> impl Iterator<Item = &str> {
+    grid.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+}
+
+/// the area covered by an ascii grid
+pub fn ascii_area(grid: &str) -> PosArea {
+    let lines: Vec<&str> = grid_lines(grid).collect();
+    let width = lines.iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    PosArea::new(0..width as Int, 0..lines.len() as Int)
+}
+
+/// normalize an ascii grid (removing indentation and empty lines)
+/// so that it can be compared to the output of `Board::to_ascii`
+pub fn normalize_ascii(grid: &str) -> String {
+    let mut s = String::new();
+    for line in grid_lines(grid) {
+        s.push_str(line);
+        s.push('\n');
+    }
+    s
+}
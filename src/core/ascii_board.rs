@@ -0,0 +1,99 @@
+//! boards written as ascii grids, to write and check tests
+//! of the game rules. Only built for the tests, or with the
+//! `test-support` feature.
+
+use {
+    anyhow::Result,
+    crate::{
+        persist::Level,
+        pos::*,
+    },
+    super::{
+        ascii::grid_lines,
+        *,
+    },
+};
+
+/// the terrain of the cells out of the ascii grid
+pub const ASCII_DEFAULT_TERRAIN: Terrain = Terrain::Stone;
+
+impl Board {
+
+    /// build a board from an ascii grid, whose top left
+    /// char is at (0, 0). Lines may be indented.
+    ///
+    /// Actors and items are on mud, and everything out of
+    /// the grid is stone. There must be exactly one lapin.
+    pub fn from_ascii(grid: &str) -> Result<Self> {
+        let mut level = Level {
+            default_terrain: ASCII_DEFAULT_TERRAIN,
+            actors: Vec::new(),
+            ..Level::default()
+        };
+        let mut lapin = None;
+        for (y, line) in grid_lines(grid).enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let pos = Pos::new(x as Int, y as Int);
+                let terrain = if let Some(terrain) = terrain_from_char(c) {
+                    terrain
+                } else if let Some(kind) = actor_kind_from_char(c) {
+                    let actor = Actor::new(kind, pos.x, pos.y);
+                    if kind != ActorKind::Lapin {
+                        level.actors.push(actor);
+                    } else if lapin.replace(actor).is_some() {
+                        return Err(anyhow!("more than one lapin in grid"));
+                    }
+                    ASCII_UNDER_TERRAIN
+                } else if let Some(kind) = item_kind_from_char(c) {
+                    level.items.push(Located::new(pos, Item { kind }));
+                    ASCII_UNDER_TERRAIN
+                } else {
+                    return Err(anyhow!("unexpected char {:?} in grid", c));
+                };
+                if terrain != level.default_terrain {
+                    level.terrains.push(Located::new(pos, terrain));
+                }
+            }
+        }
+        let lapin = lapin.ok_or_else(|| anyhow!("no lapin in grid"))?;
+        level.actors.insert(0, lapin);
+        Ok(Board::from(&level))
+    }
+
+    /// write the part of the board in the area as an ascii grid,
+    /// living actors hiding items which hide the terrain
+    pub fn to_ascii(&self, area: &PosArea) -> String {
+        let mut s = String::new();
+        for y in area.y.clone() {
+            for x in area.x.clone() {
+                let pos = Pos::new(x, y);
+                let c = match (self.actors.by_pos(pos), self.items.get(pos)) {
+                    (Some(actor), _) if !actor.state.dead => actor_kind_to_char(actor.kind),
+                    (_, Some(item)) => item_kind_to_char(item.kind),
+                    _ => terrain_to_char(self.get(pos)),
+                };
+                s.push(c);
+            }
+            s.push('\n');
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod ascii_board_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_ascii_round_trip() {
+        let grid = r#"
+            ..~~:"
+            .L#Fc#
+            w..K..
+        "#;
+        let board = Board::from_ascii(grid).unwrap();
+        assert_eq!(board.lapin_pos(), Pos::new(1, 1));
+        assert_eq!(board.to_ascii(&ascii_area(grid)), normalize_ascii(grid));
+    }
+}
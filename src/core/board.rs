@@ -14,14 +14,33 @@ use {
 
 static GAME_AREA: PosArea = PosArea::new(-1000..1000, -1000..1000);
 
+/// the part of the game state which changes
+/// during a game, which may be saved and restored
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BoardState {
+    pub actors: Vec<Actor>,
+    pub items: Vec<Located<Item>>, // sorted by lines then columns
+    pub current_player: Player,
+    pub inventory: Vec<Pos>,
+    pub link_terrains: Vec<Terrain>, // terrains of the link targets, in order
+}
+
 /// the game state
+#[derive(Clone)]
 pub struct Board {
     pub name: String,
+    pub intro: String,   // shown when the level starts
+    pub victory: String, // shown when the level is won
+    pub objectives: Vec<Objective>,
+    pub fog: bool, // whether the lapin only sees what's in its line of sight
     pub area: PosArea,
     pub terrains: PosMap<Terrain>,
     pub actors: ActorMap,
     pub items: OptionPosMap<Item>,
     pub current_player: Player, // whose turn it is
+    pub ruleset: Ruleset,
+    pub links: Vec<Link>,
+    pub inventory: Vec<Pos>, // the initial positions of the keys the lapin carries
 }
 
 impl From<&Level> for Board {
@@ -31,6 +50,10 @@ impl From<&Level> for Board {
             PosArea::empty(),
             level.default_terrain,
         );
+        board.intro = level.intro.clone();
+        board.victory = level.victory.clone();
+        board.objectives = level.objectives.clone();
+        board.fog = level.fog;
         board.reset_to(level);
         board
     }
@@ -48,11 +71,18 @@ impl Board {
         let items = OptionPosMap::new(area.clone(), None);
         Self {
             name,
+            intro: String::new(),
+            victory: String::new(),
+            objectives: Vec::new(),
+            fog: false,
             area,
             terrains,
             actors,
             items,
             current_player: Player::Lapin,
+            ruleset: Ruleset::default(),
+            links: Vec::new(),
+            inventory: Vec::new(),
         }
     }
 
@@ -60,6 +90,22 @@ impl Board {
         self.terrains.default
     }
 
+    /// the area of the level (its terrains which aren't the
+    /// default one, its actors and its items), with a margin
+    /// of one cell
+    pub fn bounding_area(&self) -> PosArea {
+        let default_terrain = self.default_terrain();
+        let area = PosDistribution::from(
+            self.terrains.iter()
+                .filter(|lc| lc.v != default_terrain)
+                .map(|lc| lc.pos)
+                .chain(self.actors.iter().map(|a| a.pos))
+                .chain(self.items.iter_some().map(|lc| lc.pos))
+        )
+        .map_or_else(PosArea::empty, |pd| pd.area);
+        PosArea::new(area.x.start - 1..area.x.end + 1, area.y.start - 1..area.y.end + 1)
+    }
+
     pub fn reset_to(&mut self, level: &Level) {
         let pos_distribution = PosDistribution::from(
             level.terrains.iter()
@@ -73,9 +119,50 @@ impl Board {
         }
         self.actors = ActorMap::new(pos_distribution.area.clone(), level.actors.clone());
         self.items = OptionPosMap::new(pos_distribution.area.clone(), None);
+        self.area = pos_distribution.area;
         for lc in &level.items {
             self.items.set_some(lc.pos, lc.v);
         }
+        self.current_player = Player::Lapin;
+        self.ruleset = level.ruleset.clone().unwrap_or_default();
+        self.links = level.links.clone();
+        self.inventory.clear();
+    }
+
+    /// give the board the ruleset of the campaign, unless
+    /// the level has its own one
+    pub fn apply_campaign_ruleset(&mut self, level: &Level, ruleset: Option<&Ruleset>) {
+        if let (None, Some(ruleset)) = (&level.ruleset, ruleset) {
+            self.ruleset = ruleset.clone();
+        }
+    }
+
+    /// take a snapshot of the part of the board which may
+    /// change during a game
+    pub fn state(&self) -> BoardState {
+        let mut items: Vec<Located<Item>> = self.items.iter_some().collect();
+        items.sort_by_key(|lc| (lc.pos.y, lc.pos.x));
+        BoardState {
+            actors: self.actors.vec(),
+            items,
+            current_player: self.current_player,
+            inventory: self.inventory.clone(),
+            link_terrains: self.links.iter().map(|link| self.get(link.target)).collect(),
+        }
+    }
+
+    /// bring back the board to a state taken with `state`
+    pub fn restore(&mut self, state: &BoardState) {
+        self.actors.reset(state.actors.clone());
+        self.items.clear();
+        for lc in &state.items {
+            self.items.set_some(lc.pos, lc.v);
+        }
+        self.current_player = state.current_player;
+        self.inventory = state.inventory.clone();
+        for (i, &terrain) in state.link_terrains.iter().enumerate() {
+            self.set(self.links[i].target, terrain);
+        }
     }
 
     pub fn lapin_pos(&self) -> Pos {
@@ -107,46 +194,139 @@ impl Board {
     pub fn get(&self, pos: Pos) -> Terrain {
         self.terrains.get(pos)
     }
+    pub fn is_closed_door(&self, pos: Pos) -> bool {
+        matches!(self.items.get(pos), Some(Item { kind: ItemKind::Door }))
+    }
+    /// the cells a firer could shoot at in the direction
+    pub fn firing_line(&self, firer: Actor, dir: Dir) -> Vec<Pos> {
+        let mut line = Vec::new();
+        let mut pos = firer.pos;
+        for _ in 0..self.ruleset.firing_range(firer) {
+            pos = pos.in_dir(dir);
+            if self.get(pos) == Terrain::Stone || self.is_closed_door(pos) {
+                break;
+            }
+            line.push(pos);
+        }
+        line
+    }
+    /// tell whether the actor may go to pos, ignoring other actors
+    /// (closed doors are walls for everybody)
+    pub fn can_enter(&self, actor: Actor, pos: Pos) -> bool {
+        self.ruleset.can_enter(actor, self.get(pos)) && !self.is_closed_door(pos)
+    }
+    /// return the index in the inventory of a key opening the door
+    fn key_for_door(&self, door: Pos) -> Option<usize> {
+        self.inventory.iter().position(|&key| {
+            self.links.iter().any(|link| link.switch == key && link.target == door)
+        })
+    }
+    /// toggle the terrains linked to the lever, when they're
+    /// toggleable and free of actors
+    fn pull_lever(&mut self, lever: Pos) {
+        for i in 0..self.links.len() {
+            let link = self.links[i];
+            if link.switch != lever || self.actors.has_pos(link.target) {
+                continue;
+            }
+            if let Some(terrain) = self.get(link.target).toggled() {
+                self.set(link.target, terrain);
+            }
+        }
+    }
+
+    /// tell whether the lapin may go in pos, maybe after having
+    /// pulled a lever or found a key (we don't check it's possible).
+    /// Actors aren't taken into account.
+    pub fn lapin_may_enter(&self, pos: Pos) -> bool {
+        let lapin = self.actors.lapin();
+        let terrain = self.get(pos);
+        self.ruleset.can_enter(lapin, terrain) || self.links.iter().any(|link| {
+            link.target == pos && terrain.toggled()
+                .is_some_and(|toggled| self.ruleset.can_enter(lapin, toggled))
+        })
+    }
 
     pub fn apply_player_move(&mut self, dir: Dir) -> MoveResult {
         if self.current_player != Player::Lapin {
             return MoveResult::Invalid;
         }
         let mut end_turn = true;
-        let pos = self.lapin_pos().in_dir(dir);
+        let mut pos = self.lapin_pos().in_dir(dir);
         if !GAME_AREA.contains(pos) {
             warn!("Lapin is too far!");
             return MoveResult::Invalid;
         }
-        if !self.actors.lapin().can_enter(self.get(pos)) {
+        if !self.ruleset.can_enter(self.actors.lapin(), self.get(pos)) {
             debug!("can't go there");
             return MoveResult::Invalid
         }
-        if let Some(actor) = self.actors.by_pos(pos) {
-            if actor.runs_after(self.actors.lapin()) {
+        let mut sliding = false;
+        loop {
+            if let Some(actor) = self.actors.by_pos(pos) {
+                if self.ruleset.runs_after(actor, self.actors.lapin()) {
+                    self.current_player = Player::None;
+                    // in order to move the lapin, we must mark
+                    // it dead first (or an error would be thrown)
+                    self.actors.state_by_id_mut(0).dead = true;
+                    self.actors.move_by_id_to_pos(0, pos).unwrap();
+                    return MoveResult::PlayerLose(format!(
+                        "You have been eaten by a *{:?}*.", actor.kind
+                    ));
+                } else if sliding {
+                    break; // the lapin stops against this actor
+                } else {
+                    return MoveResult::Invalid;
+                }
+            }
+            if self.is_closed_door(pos) {
+                if let Some(idx) = self.key_for_door(pos) {
+                    info!("Lapin opens a door");
+                    self.inventory.remove(idx);
+                    self.items.remove(pos);
+                } else if sliding {
+                    break;
+                } else {
+                    debug!("no key for this door");
+                    return MoveResult::Invalid;
+                }
+            }
+            self.actors.move_lapin_to(pos);
+            if self.get(pos) == Terrain::Grass {
                 self.current_player = Player::None;
-                // in order to move the lapin, we must mark
-                // it dead first (or an error would be thrown)
-                self.actors.state_by_id_mut(0).dead = true;
-                self.actors.move_by_id_to_pos(0, pos).unwrap();
-                return MoveResult::PlayerLose(format!(
-                    "You have been eaten by a *{:?}*.", actor.kind
-                ));
-            } else {
-                return MoveResult::Invalid;
+                return MoveResult::PlayerWin(
+                    "You're on the grass.".to_string()
+                );
             }
-        }
-        self.actors.move_lapin_to(pos);
-        if self.get(pos) == Terrain::Grass {
-            self.current_player = Player::None;
-            return MoveResult::PlayerWin(
-                "You're on the grass.".to_string()
-            );
-        }
-        if let Some(Item{ kind: ItemKind::Carrot }) = self.items.get(pos) {
-            self.items.remove(pos);
-            info!("Lapin eats a carrot and replays");
-            end_turn = false;
+            match self.items.get(pos) {
+                Some(Item{ kind: ItemKind::Carrot }) => {
+                    self.items.remove(pos);
+                    info!("Lapin eats a carrot and replays");
+                    end_turn = false;
+                }
+                Some(Item{ kind: ItemKind::Key }) => {
+                    self.items.remove(pos);
+                    info!("Lapin picks a key");
+                    self.inventory.push(pos);
+                }
+                Some(Item{ kind: ItemKind::Lever }) => {
+                    info!("Lapin pulls a lever");
+                    self.pull_lever(pos);
+                }
+                _ => {}
+            }
+            // on ice, the lapin keeps sliding until it hits something
+            if self.get(pos) != Terrain::Ice {
+                break;
+            }
+            let next = pos.in_dir(dir);
+            if !GAME_AREA.contains(next)
+                || !self.ruleset.can_enter(self.actors.lapin(), self.get(next))
+            {
+                break;
+            }
+            pos = next;
+            sliding = true;
         }
         if end_turn {
             self.current_player = Player::World;
@@ -154,6 +334,14 @@ impl Board {
         MoveResult::Ok
     }
 
+    /// let the world play its turn, with the next seed of the rng,
+    /// and return the move it played (it's kept for animation)
+    pub fn play_world(&mut self, rng: &mut WorldRng) -> (WorldMove, MoveResult) {
+        let mut world_move = WorldPlayer::from_rng(self, rng).play();
+        let move_result = self.apply_world_move(&mut world_move);
+        (world_move, move_result)
+    }
+
     pub fn apply_world_move(&mut self, world_move: &mut WorldMove) -> MoveResult {
         let mut result = MoveResult::Ok;
         self.current_player = Player::Lapin;
@@ -185,7 +373,7 @@ impl Board {
                     }
                 }
                 Action::Fires(_, target_id) => {
-                    if !self.actors.by_id(target_id).kind.is_immune_to_fire(actor.kind) {
+                    if !self.ruleset.is_immune_to_fire(self.actors.by_id(target_id), actor) {
                         let target_state = self.actors.state_by_id_mut(target_id);
                         if target_state.dead {
                             debug!("firing prevented because target already dead");
@@ -0,0 +1,172 @@
+use {
+    crate::pos::*,
+    std::collections::VecDeque,
+    super::*,
+};
+
+static DIRS: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+/// number of lapin moves explored to find a hint
+const HINT_DEPTH: usize = 4;
+
+/// the value of a position for which the lapin won
+const WIN_VALUE: i32 = 10_000;
+
+/// the number of lapin moves from each cell to the nearest
+/// grass, ignoring the other actors
+fn grass_distances(board: &Board) -> PosMap<Option<i32>> {
+    let area = board.bounding_area();
+    let mut distances = PosMap::new(area.clone(), None);
+    let mut queue = VecDeque::new();
+    for y in area.y.clone() {
+        for x in area.x.clone() {
+            let pos = Pos::new(x, y);
+            if board.get(pos) == Terrain::Grass {
+                distances.set(pos, Some(0));
+                queue.push_back(pos);
+            }
+        }
+    }
+    while let Some(pos) = queue.pop_front() {
+        let d = distances.get(pos).unwrap_or(0);
+        for &dir in &DIRS {
+            let next = pos.in_dir(dir);
+            if area.contains(next)
+                && distances.get(next).is_none()
+                && board.lapin_may_enter(next)
+            {
+                distances.set(next, Some(d + 1));
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// what happens when the lapin moves
+enum Outcome {
+    Invalid,
+    Dead,
+    Won,
+    Alive(Box<Board>, WorldRng),
+}
+
+/// apply the lapin move, then the world's reply, on a copy of the board
+fn try_move(board: &Board, dir: Dir, mut rng: WorldRng) -> Outcome {
+    let mut board = board.clone();
+    let mut move_result = board.apply_player_move(dir);
+    if matches!(move_result, MoveResult::Ok) && board.current_player == Player::World {
+        move_result = board.play_world(&mut rng).1;
+    }
+    match move_result {
+        MoveResult::Invalid => Outcome::Invalid,
+        MoveResult::PlayerLose(_) => Outcome::Dead,
+        MoveResult::PlayerWin(_) => Outcome::Won,
+        MoveResult::Ok => Outcome::Alive(Box::new(board), rng),
+    }
+}
+
+/// A look ahead of the next turns, the world playing as it
+/// will in the game (its moves only depend on the seeds).
+///
+/// As the world is slow to play, the moves after the first one
+/// aren't all explored: the lapin greedily goes toward the grass,
+/// avoiding the moves which kill it.
+struct HintSearch {
+    distances: PosMap<Option<i32>>,
+}
+
+impl HintSearch {
+    fn distance(&self, pos: Pos) -> i32 {
+        self.distances.get(pos).unwrap_or(WIN_VALUE / 2)
+    }
+    /// the value of the game after the first move: winning soon
+    /// is best, then being near the grass, and being killed in
+    /// the next turns is worst
+    fn value(&self, mut board: Box<Board>, mut rng: WorldRng) -> i32 {
+        for depth in 1..HINT_DEPTH {
+            let lapin_pos = board.lapin_pos();
+            let mut dirs = DIRS;
+            dirs.sort_by_key(|&dir| self.distance(lapin_pos.in_dir(dir)));
+            let mut next = None;
+            for dir in dirs {
+                match try_move(&board, dir, rng) {
+                    Outcome::Won => return WIN_VALUE - depth as i32,
+                    Outcome::Alive(board, rng) => {
+                        next = Some((board, rng));
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            match next {
+                Some((next_board, next_rng)) => {
+                    board = next_board;
+                    rng = next_rng;
+                }
+                None => {
+                    // the lapin is stuck, or killed whatever it does
+                    if DIRS.iter().any(|&dir| matches!(try_move(&board, dir, rng), Outcome::Dead)) {
+                        return depth as i32 - WIN_VALUE;
+                    }
+                    break;
+                }
+            }
+        }
+        -self.distance(board.lapin_pos())
+    }
+}
+
+/// find a move of the lapin which doesn't lead to its death in
+/// the next turns and brings it nearer to the grass, or None
+/// when all moves kill it.
+///
+/// The rng must be the one of the game, so that the world plays
+/// in the search as it will in the game.
+pub fn hint(board: &Board, rng: WorldRng) -> Option<Dir> {
+    let search = HintSearch {
+        distances: grass_distances(board),
+    };
+    let mut best: Option<(Dir, i32)> = None;
+    for &dir in &DIRS {
+        let value = match try_move(board, dir, rng) {
+            Outcome::Won => WIN_VALUE,
+            Outcome::Alive(board, rng) => search.value(board, rng),
+            _ => continue,
+        };
+        if best.map_or(true, |(_, best_value)| value > best_value) {
+            best = Some((dir, value));
+        }
+    }
+    best.map(|(dir, _)| dir)
+}
+
+#[cfg(test)]
+mod hint_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_hint() {
+        let board = Board::from_ascii(r#"
+            .....F
+            L.....
+            ......
+            """"""
+        "#).unwrap();
+        assert_eq!(hint(&board, WorldRng::new(0)), Some(Dir::Down));
+        // going right, the lapin would be eaten by the fox
+        let board = Board::from_ascii(r#"
+            L.F.
+            ....
+            """"
+        "#).unwrap();
+        assert_eq!(hint(&board, WorldRng::new(0)), Some(Dir::Down));
+        // there's no escape
+        let board = Board::from_ascii(r#"
+            LF
+            W.
+        "#).unwrap();
+        assert_eq!(hint(&board, WorldRng::new(0)), None);
+    }
+}
@@ -17,14 +17,24 @@ use {
 pub enum ItemKind {
     Carrot,
     Wine,
+    Key,
+    Door,
+    Lever,
 }
 impl ItemKind {
     pub fn skin(self, skin: &Skin) -> &StyledChar {
         match self {
             ItemKind::Carrot => &skin.carrot,
             ItemKind::Wine => &skin.wine,
+            ItemKind::Key => &skin.key,
+            ItemKind::Door => &skin.door,
+            ItemKind::Lever => &skin.lever,
         }
     }
+    /// tell whether the item can be linked to a target
+    pub fn is_switch(self) -> bool {
+        matches!(self, ItemKind::Key | ItemKind::Lever)
+    }
 }
 
 impl fmt::Display for ItemKind {
@@ -32,13 +42,22 @@ impl fmt::Display for ItemKind {
         match self {
             ItemKind::Carrot => write!(f, "carrot"),
             ItemKind::Wine => write!(f, "wine bottle"),
+            ItemKind::Key => write!(f, "key"),
+            ItemKind::Door => write!(f, "door"),
+            ItemKind::Lever => write!(f, "lever"),
         }
     }
 }
 
-pub static ITEMS: &[ItemKind] = &[ItemKind::Carrot, ItemKind::Wine];
+pub static ITEMS: &[ItemKind] = &[
+    ItemKind::Carrot,
+    ItemKind::Wine,
+    ItemKind::Key,
+    ItemKind::Door,
+    ItemKind::Lever,
+];
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct Item {
     pub kind: ItemKind,
 }
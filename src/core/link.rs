@@ -0,0 +1,18 @@
+use {
+    crate::pos::*,
+    serde::{Serialize, Deserialize},
+};
+
+/// a link from a switch (a key or a lever) to its target: the
+/// door the key opens or the cell whose terrain the lever toggles
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct Link {
+    pub switch: Pos,
+    pub target: Pos,
+}
+
+impl Link {
+    pub fn new(switch: Pos, target: Pos) -> Self {
+        Self { switch, target }
+    }
+}
@@ -2,21 +2,49 @@
 
 mod actor;
 mod actor_map;
+mod ascii;
+#[cfg(any(test, feature = "test-support"))]
+mod ascii_board;
 mod board;
+mod hint;
 mod item;
+mod link;
 mod move_result;
+mod objective;
 mod path;
+mod ruleset;
 mod terrain;
+mod threats;
+mod vision;
 mod world;
 mod world_move;
+mod world_rng;
 
 pub use {
     actor::*,
     actor_map::*,
     board::*,
+    hint::*,
     item::*,
+    link::*,
     move_result::*,
+    objective::*,
+    ruleset::*,
     terrain::*,
+    threats::*,
+    vision::*,
     world::*,
     world_move::*,
+    world_rng::*,
+};
+
+pub(crate) use ascii::*;
+
+#[cfg(any(test, feature = "test-support"))]
+pub use {
+    ascii::{
+        ascii_area,
+        normalize_ascii,
+    },
+    ascii_board::*,
 };
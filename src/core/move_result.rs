@@ -9,7 +9,7 @@ pub enum MoveResult {
     PlayerLose(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Player {
     Lapin, // played by a presumed human
     World, // the rest
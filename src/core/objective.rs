@@ -0,0 +1,97 @@
+use {
+    serde::{Serialize, Deserialize},
+    std::fmt,
+    super::*,
+};
+
+/// an optional goal of a level, harder than just reaching
+/// the grass, which gives stars when reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Objective {
+
+    /// win in at most this number of lapin moves
+    MaxMoves(usize),
+
+    /// eat all the carrots before reaching the grass
+    AllCarrots,
+
+    /// get all the hunters drunk
+    DrunkHunters,
+
+    /// have all the foxes eaten (usually by the knight)
+    NoFoxLeft,
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MaxMoves(n) => write!(f, "win in at most {} moves", n),
+            Self::AllCarrots => write!(f, "eat all carrots"),
+            Self::DrunkHunters => write!(f, "get the hunters drunk"),
+            Self::NoFoxLeft => write!(f, "have all foxes eaten"),
+        }
+    }
+}
+
+impl Objective {
+    /// tell whether the objective is reached on the board of a
+    /// won game, after the given number of lapin moves
+    pub fn is_reached(self, board: &Board, moves: usize) -> bool {
+        let alive = |kind| board.actors.iter()
+            .filter(move |actor| actor.kind == kind && !actor.state.dead);
+        match self {
+            Self::MaxMoves(n) => moves <= n,
+            Self::AllCarrots => board.items.iter_some()
+                .all(|lc| lc.v.kind != ItemKind::Carrot),
+            Self::DrunkHunters => alive(ActorKind::Hunter)
+                .all(|actor| actor.state.drunk),
+            Self::NoFoxLeft => alive(ActorKind::Fox).next().is_none(),
+        }
+    }
+}
+
+/// the stars of a won game: one for the win, and two more
+/// shared by the objectives (three when there's none)
+pub fn stars(objectives: &[Objective], board: &Board, moves: usize) -> usize {
+    if objectives.is_empty() {
+        return MAX_STARS;
+    }
+    let reached = objectives.iter()
+        .filter(|o| o.is_reached(board, moves))
+        .count();
+    1 + (MAX_STARS - 1) * reached / objectives.len()
+}
+
+pub const MAX_STARS: usize = 3;
+
+/// the stars as shown to the player, e.g. "★★☆"
+pub fn star_marks(stars: usize) -> String {
+    (0..MAX_STARS)
+        .map(|i| if i < stars { '★' } else { '☆' })
+        .collect()
+}
+
+#[cfg(test)]
+mod objective_tests {
+
+    use {
+        super::*,
+        crate::pos::Pos,
+    };
+
+    #[test]
+    fn test_stars() {
+        let mut board = Board::from_ascii(r#"
+            L.c.H
+            ....F
+        "#).unwrap();
+        let objectives = [Objective::MaxMoves(4), Objective::AllCarrots];
+        assert_eq!(stars(&[], &board, 10), 3);
+        assert_eq!(stars(&objectives, &board, 10), 1);
+        assert_eq!(stars(&objectives, &board, 3), 2);
+        board.items.remove(Pos::new(2, 0));
+        assert_eq!(stars(&objectives, &board, 3), 3);
+        assert!(!Objective::DrunkHunters.is_reached(&board, 3));
+        assert!(!Objective::NoFoxLeft.is_reached(&board, 3));
+    }
+}
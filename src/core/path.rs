@@ -52,7 +52,7 @@ impl PartialOrd for ValuedPos {
 pub enum Goal {
     Pos(Pos),
     Terrain(Terrain),
-    ActorKinds(&'static[ActorKind]),
+    ActorKinds(ActorKindSet),
 }
 
 pub struct PathFinder<'b> {
@@ -78,7 +78,7 @@ impl<'b> PathFinder<'b> {
     // This function will usually return false for the goal. It's
     // thus necessary to check the goal before calling this one.
     fn can_enter(&self, pos: Pos) -> bool {
-        self.actor.can_enter(self.board.get(pos)) && !self.board.actors.has_pos(pos)
+        self.board.can_enter(self.actor, pos) && !self.board.actors.has_pos(pos)
     }
 
     /// tells whether the pos is a/the goal
@@ -87,7 +87,7 @@ impl<'b> PathFinder<'b> {
             Goal::Pos(goal_pos) => goal_pos == pos,
             Goal::Terrain(terrain) => self.board.get(pos) == terrain && !self.board.actors.has_pos(pos),
             Goal::ActorKinds(kinds) => self.board.actors.by_pos(pos)
-                .map_or(false, |actor| kinds.contains(&actor.kind) && self.actor.can_enter(self.board.get(pos))),
+                .map_or(false, |actor| kinds.contains(actor.kind) && self.board.can_enter(self.actor, pos)),
         }
     }
 
@@ -0,0 +1,347 @@
+use {
+    crate::{
+        pos::*,
+    },
+    serde::{Serialize, Deserialize},
+    super::*,
+};
+
+/// the distance at which firers fire in the default rules
+pub const DEFAULT_FIRING_RANGE: Int = 7;
+
+/// a set of actor kinds, serialized as a list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "Vec<ActorKind>", into = "Vec<ActorKind>")]
+pub struct ActorKindSet {
+    bits: u16,
+}
+
+impl ActorKindSet {
+    pub fn empty() -> Self {
+        Self { bits: 0 }
+    }
+    pub fn all() -> Self {
+        Self::of(ACTORS)
+    }
+    pub fn of(kinds: &[ActorKind]) -> Self {
+        let mut set = Self::empty();
+        for &kind in kinds {
+            set.insert(kind);
+        }
+        set
+    }
+    pub fn insert(&mut self, kind: ActorKind) {
+        self.bits |= 1 << kind as u16;
+    }
+    pub fn contains(self, kind: ActorKind) -> bool {
+        self.bits & (1 << kind as u16) != 0
+    }
+    pub fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+}
+impl From<Vec<ActorKind>> for ActorKindSet {
+    fn from(kinds: Vec<ActorKind>) -> Self {
+        Self::of(&kinds)
+    }
+}
+impl From<ActorKindSet> for Vec<ActorKind> {
+    fn from(set: ActorKindSet) -> Self {
+        ACTORS.iter().filter(|&&k| set.contains(k)).copied().collect()
+    }
+}
+
+/// a set of terrains, serialized as a list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "Vec<Terrain>", into = "Vec<Terrain>")]
+pub struct TerrainSet {
+    bits: u16,
+}
+
+impl TerrainSet {
+    pub fn empty() -> Self {
+        Self { bits: 0 }
+    }
+    pub fn of(terrains: &[Terrain]) -> Self {
+        let mut set = Self::empty();
+        for &terrain in terrains {
+            set.insert(terrain);
+        }
+        set
+    }
+    pub fn insert(&mut self, terrain: Terrain) {
+        self.bits |= 1 << terrain as u16;
+    }
+    pub fn contains(self, terrain: Terrain) -> bool {
+        self.bits & (1 << terrain as u16) != 0
+    }
+}
+impl From<Vec<Terrain>> for TerrainSet {
+    fn from(terrains: Vec<Terrain>) -> Self {
+        Self::of(&terrains)
+    }
+}
+impl From<TerrainSet> for Vec<Terrain> {
+    fn from(set: TerrainSet) -> Self {
+        TERRAINS.iter().filter(|&&t| set.contains(t)).copied().collect()
+    }
+}
+
+/// the rules applying to one kind of actor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ActorRules {
+
+    /// the terrains the actor can walk on
+    pub can_enter: TerrainSet,
+
+    /// the actors it kills when moving on them
+    pub eats: ActorKindSet,
+
+    /// the actors which it kills when they try to move on it,
+    /// and which it chases in priority
+    pub runs_after: ActorKindSet,
+
+    /// the actors it walks to (when it's an eater or a firer)
+    pub preys: ActorKindSet,
+
+    /// the actors it aims and fires at
+    pub fires_on: ActorKindSet,
+
+    /// the actors it aims and fires at when drunk
+    pub fires_on_when_drunk: ActorKindSet,
+
+    /// how far it fires
+    pub firing_range: Int,
+
+    /// the firers whose fire doesn't kill it
+    pub immune_to_fire_of: ActorKindSet,
+
+    /// the terrains in which it's hidden from firers
+    pub hides_in: TerrainSet,
+
+    /// whether it sees (and fires on) hidden actors
+    pub sees_hidden: bool,
+}
+
+/// the rules of the game: what actors can do and to whom.
+///
+/// The behavior of each kind of actor (eater, firer, grazer)
+/// isn't part of the rules.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "RulesetPatch")]
+pub struct Ruleset {
+    pub lapin: ActorRules,
+    pub knight: ActorRules,
+    pub wolf: ActorRules,
+    pub fox: ActorRules,
+    pub hunter: ActorRules,
+    pub sheep: ActorRules,
+    pub dragon: ActorRules,
+}
+
+/// the rules of a kind of actor as read from a file: the missing
+/// properties keep the default rules of the kind
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ActorRulesPatch {
+    can_enter: Option<TerrainSet>,
+    eats: Option<ActorKindSet>,
+    runs_after: Option<ActorKindSet>,
+    preys: Option<ActorKindSet>,
+    fires_on: Option<ActorKindSet>,
+    fires_on_when_drunk: Option<ActorKindSet>,
+    firing_range: Option<Int>,
+    immune_to_fire_of: Option<ActorKindSet>,
+    hides_in: Option<TerrainSet>,
+    sees_hidden: Option<bool>,
+}
+
+impl ActorRulesPatch {
+    fn apply(self, rules: &mut ActorRules) {
+        if let Some(v) = self.can_enter { rules.can_enter = v; }
+        if let Some(v) = self.eats { rules.eats = v; }
+        if let Some(v) = self.runs_after { rules.runs_after = v; }
+        if let Some(v) = self.preys { rules.preys = v; }
+        if let Some(v) = self.fires_on { rules.fires_on = v; }
+        if let Some(v) = self.fires_on_when_drunk { rules.fires_on_when_drunk = v; }
+        if let Some(v) = self.firing_range { rules.firing_range = v; }
+        if let Some(v) = self.immune_to_fire_of { rules.immune_to_fire_of = v; }
+        if let Some(v) = self.hides_in { rules.hides_in = v; }
+        if let Some(v) = self.sees_hidden { rules.sees_hidden = v; }
+    }
+}
+
+/// a ruleset as read from a file, where the missing kinds
+/// and properties keep the default rules
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct RulesetPatch {
+    lapin: ActorRulesPatch,
+    knight: ActorRulesPatch,
+    wolf: ActorRulesPatch,
+    fox: ActorRulesPatch,
+    hunter: ActorRulesPatch,
+    sheep: ActorRulesPatch,
+    dragon: ActorRulesPatch,
+}
+
+impl From<RulesetPatch> for Ruleset {
+    fn from(patch: RulesetPatch) -> Self {
+        let mut ruleset = Ruleset::default();
+        patch.lapin.apply(&mut ruleset.lapin);
+        patch.knight.apply(&mut ruleset.knight);
+        patch.wolf.apply(&mut ruleset.wolf);
+        patch.fox.apply(&mut ruleset.fox);
+        patch.hunter.apply(&mut ruleset.hunter);
+        patch.sheep.apply(&mut ruleset.sheep);
+        patch.dragon.apply(&mut ruleset.dragon);
+        ruleset
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        use {ActorKind::*, Terrain::*};
+        let walker = ActorRules {
+            can_enter: TerrainSet::of(&[Mud, Grass, Sand, Ice, Bridge, Bush]),
+            ..ActorRules::default()
+        };
+        Self {
+            lapin: ActorRules {
+                can_enter: TerrainSet::of(&[Mud, Grass, Sand, Ice, Bridge, Bush, Hole]),
+                hides_in: TerrainSet::of(&[Bush]),
+                ..walker
+            },
+            knight: ActorRules {
+                can_enter: TerrainSet::of(&[Mud, Grass, Ice, Bridge, Bush]),
+                eats: ActorKindSet::of(&[Fox, Hunter, Wolf]),
+                runs_after: ActorKindSet::of(&[Fox]),
+                preys: ActorKindSet::of(&[Wolf, Fox, Hunter]),
+                immune_to_fire_of: ActorKindSet::of(&[Hunter]),
+                ..walker
+            },
+            wolf: ActorRules {
+                eats: ActorKindSet::of(&[Hunter, Sheep, Lapin]),
+                runs_after: ActorKindSet::of(&[Hunter, Sheep, Lapin]),
+                preys: ActorKindSet::of(&[Lapin, Hunter]),
+                ..walker
+            },
+            fox: ActorRules {
+                eats: ActorKindSet::of(&[Lapin]),
+                runs_after: ActorKindSet::of(&[Lapin]),
+                preys: ActorKindSet::of(&[Lapin]),
+                ..walker
+            },
+            hunter: ActorRules {
+                runs_after: ActorKindSet::of(&[Lapin]),
+                preys: ActorKindSet::of(&[Lapin, Wolf, Fox]),
+                fires_on: ActorKindSet::of(&[Fox, Knight, Lapin, Wolf]),
+                fires_on_when_drunk: ActorKindSet::all(),
+                firing_range: DEFAULT_FIRING_RANGE,
+                ..walker
+            },
+            sheep: walker,
+            dragon: ActorRules {
+                // dragon flies over water, bridges included
+                can_enter: TerrainSet::of(&[Mud, Grass, Sand, Water, Ice, Bridge, Bush]),
+                eats: ActorKindSet::all(),
+                runs_after: ActorKindSet::all(),
+                preys: ActorKindSet::of(&[Lapin, Hunter, Wolf, Fox, Sheep]),
+                fires_on: ActorKindSet::all(),
+                fires_on_when_drunk: ActorKindSet::all(),
+                firing_range: DEFAULT_FIRING_RANGE,
+                sees_hidden: true,
+                ..walker
+            },
+        }
+    }
+}
+
+impl Ruleset {
+    pub fn rules(&self, kind: ActorKind) -> &ActorRules {
+        use ActorKind::*;
+        match kind {
+            Lapin => &self.lapin,
+            Knight => &self.knight,
+            Wolf => &self.wolf,
+            Fox => &self.fox,
+            Hunter => &self.hunter,
+            Sheep => &self.sheep,
+            Dragon => &self.dragon,
+        }
+    }
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+    /// tell whether the actor may go on the terrain. A bridge
+    /// counts as water for the actors which can swim
+    pub fn can_enter(&self, actor: Actor, terrain: Terrain) -> bool {
+        let can_enter = self.rules(actor.kind).can_enter;
+        can_enter.contains(terrain)
+            || (terrain == Terrain::Bridge && can_enter.contains(Terrain::Water))
+    }
+    pub fn eats(&self, actor: Actor, other: Actor) -> bool {
+        self.rules(actor.kind).eats.contains(other.kind)
+    }
+    pub fn runs_after(&self, actor: Actor, other: Actor) -> bool {
+        self.rules(actor.kind).runs_after.contains(other.kind)
+    }
+    pub fn preys(&self, actor: Actor) -> ActorKindSet {
+        self.rules(actor.kind).preys
+    }
+    pub fn fires_on(&self, actor: Actor, other: Actor) -> bool {
+        let rules = self.rules(actor.kind);
+        if actor.state.drunk {
+            rules.fires_on_when_drunk.contains(other.kind)
+        } else {
+            rules.fires_on.contains(other.kind)
+        }
+    }
+    pub fn firing_range(&self, actor: Actor) -> Int {
+        self.rules(actor.kind).firing_range
+    }
+    pub fn is_immune_to_fire(&self, target: Actor, firer: Actor) -> bool {
+        self.rules(target.kind).immune_to_fire_of.contains(firer.kind)
+    }
+    /// tell whether the firer sees the target, which is on the terrain
+    pub fn sees(&self, firer: Actor, target: Actor, terrain: Terrain) -> bool {
+        self.rules(firer.kind).sees_hidden
+            || !self.rules(target.kind).hides_in.contains(terrain)
+    }
+}
+
+#[cfg(test)]
+mod ruleset_tests {
+
+    use {
+        super::*,
+        crate::persist::{self, SerdeFormat},
+    };
+
+    #[test]
+    fn test_ruleset_serialization() {
+        let mut ruleset = Ruleset::default();
+        ruleset.wolf.can_enter.insert(Terrain::Water);
+        ruleset.hunter.firing_range = 4;
+        for &format in &[SerdeFormat::Json, SerdeFormat::MessagePack] {
+            let mut bytes = Vec::new();
+            persist::write(&mut bytes, &ruleset, format, false).unwrap();
+            let read: Ruleset = persist::read_bytes(&bytes, format).unwrap();
+            assert_eq!(read, ruleset);
+        }
+    }
+
+    #[test]
+    fn test_partial_ruleset() {
+        let ruleset: Ruleset = serde_json::from_str(r#"{
+            "wolf": { "can_enter": ["Grass", "Water"] },
+            "hunter": { "firing_range": 4 }
+        }"#).unwrap();
+        let default = Ruleset::default();
+        assert_eq!(ruleset.wolf.can_enter, TerrainSet::of(&[Terrain::Grass, Terrain::Water]));
+        assert_eq!(ruleset.wolf.eats, default.wolf.eats);
+        assert_eq!(ruleset.hunter.firing_range, 4);
+        assert_eq!(ruleset.hunter.fires_on, default.hunter.fires_on);
+        assert_eq!(ruleset.fox, default.fox);
+    }
+}
@@ -24,6 +24,12 @@ pub enum Terrain {
     Grass,
     Water,
     Sand,
+    /// the lapin slides on it until it hits something, the
+    /// other actors walk on it one cell at a time
+    Ice,
+    Bridge,
+    Bush,
+    Hole,
 }
 
 pub static TERRAINS: &[Terrain] = &[
@@ -32,6 +38,10 @@ pub static TERRAINS: &[Terrain] = &[
     Terrain::Grass,
     Terrain::Water,
     Terrain::Sand,
+    Terrain::Ice,
+    Terrain::Bridge,
+    Terrain::Bush,
+    Terrain::Hole,
 ];
 
 impl fmt::Display for Terrain {
@@ -42,6 +52,10 @@ impl fmt::Display for Terrain {
             Terrain::Grass => write!(f, "grass"),
             Terrain::Water => write!(f, "water"),
             Terrain::Sand => write!(f, "sand"),
+            Terrain::Ice => write!(f, "ice"),
+            Terrain::Bridge => write!(f, "bridge"),
+            Terrain::Bush => write!(f, "bush"),
+            Terrain::Hole => write!(f, "hole"),
         }
     }
 }
@@ -53,6 +67,20 @@ impl Terrain {
             Terrain::Grass => skin.grass,
             Terrain::Water => skin.water,
             Terrain::Sand => skin.sand,
+            Terrain::Ice => skin.ice,
+            Terrain::Bridge => skin.bridge,
+            Terrain::Bush => skin.bush,
+            Terrain::Hole => skin.hole,
+        }
+    }
+    /// the terrain a lever turns this one into, if any
+    pub fn toggled(self) -> Option<Terrain> {
+        match self {
+            Terrain::Water => Some(Terrain::Bridge),
+            Terrain::Bridge => Some(Terrain::Water),
+            Terrain::Stone => Some(Terrain::Mud),
+            Terrain::Mud => Some(Terrain::Stone),
+            _ => None,
         }
     }
     pub fn bg_as_styled_char(&self, skin: &Skin) -> StyledChar {
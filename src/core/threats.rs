@@ -0,0 +1,98 @@
+use {
+    crate::pos::*,
+    super::*,
+};
+
+static DIRS: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+/// what the enemies intend to do, computed without changing
+/// the board, to help understand (or design) a level
+pub struct Threats {
+    /// what the world would play if it was its turn now
+    pub world_move: WorldMove,
+    /// the cells in the aim of the firers, up to their range,
+    /// with the firer
+    pub firing_lines: Vec<(Pos, Dir, ActorId)>,
+    /// the cells next to the lapin where it would be killed,
+    /// with the actor killing it
+    pub lethal: Vec<(Pos, ActorId)>,
+}
+
+/// find the actor which would kill the lapin going in the direction,
+/// the world then playing with the given seed
+fn killer(board: &Board, dir: Dir, seed: usize) -> Option<ActorId> {
+    let mut board = board.clone();
+    match board.apply_player_move(dir) {
+        MoveResult::PlayerLose(_) => {
+            // the lapin went on its eater
+            let lapin_pos = board.lapin_pos();
+            (1..board.actors.len()).find(|&id| board.actors.by_id(id).pos == lapin_pos)
+        }
+        MoveResult::Ok if board.current_player == Player::World => {
+            let mut world_move = WorldPlayer::new(&board, seed).play();
+            match board.apply_world_move(&mut world_move) {
+                MoveResult::PlayerLose(_) => world_move.actor_moves.iter()
+                    .find(|actor_move| actor_move.target_id() == Some(0))
+                    .map(|actor_move| actor_move.actor_id),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+impl Threats {
+    /// compute the threats with the seed of the next world move
+    pub fn compute(board: &Board, seed: usize) -> Self {
+        let world_move = WorldPlayer::new(board, seed).play();
+        let mut firing_lines = Vec::new();
+        for id in 1..board.actors.len() {
+            let actor = board.actors.by_id(id);
+            let planned = world_move.actor_moves.iter()
+                .find(|actor_move| actor_move.actor_id == id)
+                .map(|actor_move| actor_move.action);
+            let aim = match planned {
+                Some(Action::Aims(dir)) => Some(dir),
+                Some(Action::StopsAiming) => None,
+                _ => actor.state.aim,
+            };
+            if let Some(dir) = aim {
+                for pos in board.firing_line(actor, dir) {
+                    firing_lines.push((pos, dir, id));
+                }
+            }
+        }
+        let lapin_pos = board.lapin_pos();
+        let lethal = DIRS.iter()
+            .filter_map(|&dir| {
+                killer(board, dir, seed).map(|id| (lapin_pos.in_dir(dir), id))
+            })
+            .collect();
+        Self {
+            world_move,
+            firing_lines,
+            lethal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod threats_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_threats() {
+        let board = Board::from_ascii(r#"
+            L.F
+            ...
+            ...
+            H..
+        "#).unwrap();
+        let threats = Threats::compute(&board, 0);
+        assert_eq!(threats.lethal, vec![(Pos::new(1, 0), 1)]);
+        assert!(threats.world_move.actor_moves.iter().any(|m| m.actor_id == 1));
+        // the hunter aims at the lapin
+        assert!(threats.firing_lines.contains(&(Pos::new(0, 1), Dir::Up, 2)));
+    }
+}
@@ -0,0 +1,164 @@
+use {
+    crate::pos::*,
+    super::*,
+};
+
+/// how far outside the bounding area of the level, or around
+/// its own position, the lapin may see
+const VISION_MARGIN: Int = 3;
+
+/// tell whether the lapin at `from` can see the `to` cell,
+/// that is whether there's no stone on the line between them
+/// (the stone at `to`, if any, is visible)
+pub fn in_sight(terrains: &PosMap<Terrain>, from: Pos, to: Pos) -> bool {
+    let dx = (to.x - from.x).abs();
+    let dy = -(to.y - from.y).abs();
+    let sx = if from.x < to.x { 1 } else { -1 };
+    let sy = if from.y < to.y { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut pos = from;
+    while pos != to {
+        if pos != from && terrains.get(pos) == Terrain::Stone {
+            return false;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            pos.x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            pos.y += sy;
+        }
+    }
+    true
+}
+
+fn with_margin(area: PosArea) -> PosArea {
+    PosArea::new(
+        area.x.start - VISION_MARGIN .. area.x.end + VISION_MARGIN,
+        area.y.start - VISION_MARGIN .. area.y.end + VISION_MARGIN,
+    )
+}
+
+/// what the lapin currently sees of the board, and what
+/// it remembers of it, in a fog of war level
+#[derive(Debug, Clone)]
+pub struct Vision {
+    board_area: PosArea, // bounding area of the level, with margin
+    visible: PosSet,
+    seen: PosSet, // cells seen at least once
+}
+
+impl Vision {
+    pub fn new(board: &Board) -> Self {
+        let board_area = with_margin(board.bounding_area());
+        let mut vision = Self {
+            visible: PosSet::from(board_area.clone()),
+            seen: PosSet::from(board_area.clone()),
+            board_area,
+        };
+        vision.update(board);
+        vision
+    }
+    /// compute the visible cells from the current lapin
+    /// position, and remember them.
+    /// The area where sight is computed follows the lapin,
+    /// which may have walked out of the level's area
+    pub fn update(&mut self, board: &Board) {
+        let lapin_pos = board.lapin_pos();
+        let mut area = self.board_area.clone();
+        let lapin_area = with_margin(PosArea::from_pos(lapin_pos));
+        area.grow_to(Pos::new(lapin_area.x.start, lapin_area.y.start));
+        area.grow_to(Pos::new(lapin_area.x.end - 1, lapin_area.y.end - 1));
+        self.visible.clear();
+        self.visible.insert(lapin_pos);
+        self.seen.insert(lapin_pos);
+        for y in area.y.clone() {
+            for x in area.x.clone() {
+                let pos = Pos::new(x, y);
+                if in_sight(&board.terrains, lapin_pos, pos) {
+                    self.visible.insert(pos);
+                    self.seen.insert(pos);
+                }
+            }
+        }
+    }
+    pub fn is_visible(&self, pos: Pos) -> bool {
+        self.visible.has_key(pos)
+    }
+    /// tell whether the cell was seen but isn't visible now
+    pub fn is_remembered(&self, pos: Pos) -> bool {
+        self.seen.has_key(pos) && !self.visible.has_key(pos)
+    }
+    /// remove from the board the actors the lapin doesn't see,
+    /// so that a search on it only knows what the player knows
+    pub fn hide_unseen_actors(&self, board: &mut Board) {
+        let actors = board.actors.iter()
+            .filter(|actor| self.is_visible(actor.pos))
+            .cloned()
+            .collect();
+        board.actors.reset(actors);
+    }
+}
+
+#[cfg(test)]
+mod vision_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_walls_block_sight() {
+        let mut board = Board::from_ascii(r#"
+            L..#..F
+            ...#...
+            .......
+        "#).unwrap();
+        let mut vision = Vision::new(&board);
+        assert!(vision.is_visible(Pos::new(2, 0)));
+        assert!(vision.is_visible(Pos::new(3, 0))); // the wall itself
+        assert!(!vision.is_visible(Pos::new(6, 0))); // the fox is hidden
+        assert!(vision.is_visible(Pos::new(3, 2)));
+        assert!(!vision.is_visible(Pos::new(6, 2)));
+        board.actors.move_lapin_to(Pos::new(5, 1));
+        vision.update(&board);
+        assert!(vision.is_visible(Pos::new(6, 0)));
+        assert!(vision.is_remembered(Pos::new(0, 1)));
+        assert!(!vision.is_remembered(Pos::new(6, 0)));
+    }
+
+    #[test]
+    fn test_sight_follows_lapin_out_of_level() {
+        let mut board = Board::from_ascii(r#"
+            L..#..F
+            ...#...
+        "#).unwrap();
+        board.terrains.default = Terrain::Mud; // walkable outside
+        let mut vision = Vision::new(&board);
+        let far = Pos::new(-20, 10);
+        assert!(!vision.is_visible(far));
+        board.actors.move_lapin_to(far);
+        vision.update(&board);
+        assert!(vision.is_visible(far));
+        assert!(vision.is_visible(Pos::new(-21, 11)));
+        assert!(vision.is_visible(Pos::new(-20 - VISION_MARGIN, 10 + VISION_MARGIN)));
+        assert!(!vision.is_remembered(far));
+        board.actors.move_lapin_to(Pos::new(0, 0));
+        vision.update(&board);
+        assert!(vision.is_remembered(far));
+    }
+
+    #[test]
+    fn test_hide_unseen_actors() {
+        let mut board = Board::from_ascii(r#"
+            L.F#.F
+            ...#..
+        "#).unwrap();
+        let vision = Vision::new(&board);
+        vision.hide_unseen_actors(&mut board);
+        assert_eq!(board.actors.len(), 2);
+        assert_eq!(board.lapin_pos(), Pos::new(0, 0));
+        assert!(board.actors.by_pos(Pos::new(2, 0)).is_some());
+        assert!(board.actors.by_pos(Pos::new(5, 0)).is_none());
+    }
+}
@@ -6,8 +6,6 @@ use {
     super::*,
 };
 
-pub const FIRING_RANGE: Int = 7;
-
 pub struct WorldPlayer<'t> {
     board: &'t Board,
     seed: usize,
@@ -21,6 +19,11 @@ impl<'t> WorldPlayer<'t> {
         }
     }
 
+    /// create a world player with the next seed of the rng
+    pub fn from_rng(board: &'t Board, rng: &mut WorldRng) -> Self {
+        Self::new(board, rng.next_seed())
+    }
+
     fn move_to_goal(
         &self,
         actor_id: ActorId,
@@ -32,7 +35,7 @@ impl<'t> WorldPlayer<'t> {
             &self.board,
             self.seed,
         );
-        let hint = if actor.kind.runs_after(ActorKind::Lapin) {
+        let hint = if self.board.ruleset.runs_after(actor, self.board.actors.lapin()) {
             // waiting for bool.then_some to be not nightly (or better bool.map)
             Some(self.board.lapin_pos())
         } else {
@@ -57,12 +60,16 @@ impl<'t> WorldPlayer<'t> {
     }
 
     fn find_lapin_eater_move(&self, actor_id: ActorId, actor: Actor) -> Option<ActorMove> {
-        if let Some(dir) = actor.pos.dir_to(self.board.lapin_pos()) {
-            // we can make a direct kill (may be a diagonal move)
-            return Some(ActorMove::new(
-                actor_id,
-                Action::Eats(dir, 0),
-            ));
+        let lapin_pos = self.board.lapin_pos();
+        if let Some(dir) = actor.pos.dir_to(lapin_pos) {
+            // we can make a direct kill (may be a diagonal move),
+            // unless the lapin hides where we can't go
+            if self.board.can_enter(actor, lapin_pos) {
+                return Some(ActorMove::new(
+                    actor_id,
+                    Action::Eats(dir, 0),
+                ));
+            }
         }
         self.move_to_goal(
             actor_id,
@@ -78,12 +85,12 @@ impl<'t> WorldPlayer<'t> {
                 continue;
             }
             let other = self.board.actors.by_id(other_id);
-            if !actor.eats(other) {
+            if !self.board.ruleset.eats(actor, other) {
                 continue;
             }
             if let Some(dir) = actor.pos.dir_to(other.pos) {
                 // we can make a direct kill (may be a diagonal move)
-                if actor.can_enter(self.board.get(other.pos)) {
+                if self.board.can_enter(actor, other.pos) {
                     return Some(ActorMove::new(
                         actor_id,
                         Action::Eats(dir, other_id),
@@ -94,7 +101,7 @@ impl<'t> WorldPlayer<'t> {
         self.move_to_goal(
             actor_id,
             actor,
-            path::Goal::ActorKinds(actor.preys().unwrap()),
+            path::Goal::ActorKinds(self.board.ruleset.preys(actor)),
         )
     }
 
@@ -105,7 +112,10 @@ impl<'t> WorldPlayer<'t> {
                 continue;
             }
             let other = self.board.actors.by_id(other_id);
-            if !actor.fires_on(other) {
+            if !self.board.ruleset.fires_on(actor, other) {
+                continue;
+            }
+            if !self.board.ruleset.sees(actor, other, self.board.get(other.pos)) {
                 continue;
             }
             let dist = Pos::manhattan_distance(actor.pos, other.pos);
@@ -120,19 +130,21 @@ impl<'t> WorldPlayer<'t> {
     }
 
     fn can_enter(&self, actor: Actor, pos: Pos) -> bool {
-        actor.can_enter(self.board.get(pos))
-            && !self.board.actors.has_pos(pos)
+        self.board.can_enter(actor, pos) && !self.board.actors.has_pos(pos)
     }
 
 
     fn find_firer_move(&self, actor_id: ActorId, actor: Actor) -> Option<ActorMove> {
         // we first check whether we have a target in the firing line
+        let firing_range = self.board.ruleset.firing_range(actor);
         if let Some(dir) = actor.state.aim {
             let mut pos = actor.pos;
-            for _ in 0..FIRING_RANGE {
+            for _ in 0..firing_range {
                 pos = pos.in_dir(dir);
                 if let Some((target_id, target)) = self.board.actors.id_actor_by_pos(pos) {
-                    if actor.fires_on(target) {
+                    if self.board.ruleset.fires_on(actor, target)
+                        && self.board.ruleset.sees(actor, target, self.board.get(pos))
+                    {
                         // fire!
                         return Some(ActorMove::new(
                             actor_id,
@@ -140,7 +152,7 @@ impl<'t> WorldPlayer<'t> {
                         ));
                     }
                 }
-                if self.board.get(pos) == Terrain::Stone {
+                if self.board.get(pos) == Terrain::Stone || self.board.is_closed_door(pos) {
                     break;
                 }
             }
@@ -148,7 +160,7 @@ impl<'t> WorldPlayer<'t> {
         // if there's a possible target in range, we try to lock aim on it
         let nearest_target = self.nearest_fire_target(actor_id, actor);
         if let Some((other, dist)) = nearest_target {
-            if dist <= FIRING_RANGE {
+            if dist <= firing_range {
                 let quadrant_dir = actor.pos.quadrant_to(other.pos);
                 // at this point we know the target isn't in the firing line
                 // (or we would have fired)
@@ -180,7 +192,7 @@ impl<'t> WorldPlayer<'t> {
             self.move_to_goal(
                 actor_id,
                 actor,
-                path::Goal::ActorKinds(actor.preys().unwrap()),
+                path::Goal::ActorKinds(self.board.ruleset.preys(actor)),
             )
         }
     }
@@ -198,6 +210,10 @@ impl<'t> WorldPlayer<'t> {
         }
     }
 
+    /// compute the moves of all actors (but the lapin).
+    ///
+    /// Actors are computed in parallel but the result only
+    /// depends on the board and the seed
     pub fn play(self) -> WorldMove {
         let actor_moves = (1..self.board.actors.len())
             .into_par_iter()
@@ -206,3 +222,60 @@ impl<'t> WorldPlayer<'t> {
         WorldMove { actor_moves }
     }
 }
+
+#[cfg(test)]
+mod world_tests {
+
+    use super::*;
+
+    fn arena() -> Board {
+        let mut board = Board::new("arena".to_string(), PosArea::empty(), Terrain::Stone);
+        board.set_range(0..12, 0..8, Terrain::Mud);
+        board.set_v_line(6, 2..6, Terrain::Stone);
+        board.set(Pos::new(11, 7), Terrain::Grass);
+        board.add_actor_in(ActorKind::Fox, 10, 2).unwrap();
+        board.add_actor_in(ActorKind::Wolf, 9, 6).unwrap();
+        board.add_actor_in(ActorKind::Knight, 2, 6).unwrap();
+        board.add_actor_in(ActorKind::Sheep, 5, 5).unwrap();
+        board.add_actor_in(ActorKind::Hunter, 11, 0).unwrap();
+        board
+    }
+
+    /// play a game with the given number of threads and
+    /// return all the world moves
+    fn play_game(seed: usize, nb_threads: usize) -> Vec<WorldMove> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(nb_threads)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            let mut board = arena();
+            let mut rng = WorldRng::new(seed);
+            let mut world_moves = Vec::new();
+            for &dir in &[Dir::Right, Dir::Down, Dir::Right, Dir::Down, Dir::Right, Dir::Down] {
+                if !matches!(board.apply_player_move(dir), MoveResult::Ok) {
+                    break;
+                }
+                if board.current_player != Player::World {
+                    continue;
+                }
+                let (world_move, move_result) = board.play_world(&mut rng);
+                world_moves.push(world_move);
+                if !matches!(move_result, MoveResult::Ok) {
+                    break;
+                }
+            }
+            world_moves
+        })
+    }
+
+    #[test]
+    fn test_same_seed_same_world_moves() {
+        for seed in 0..5 {
+            let reference = play_game(seed, 1);
+            assert!(!reference.is_empty());
+            assert_eq!(play_game(seed, 1), reference);
+            assert_eq!(play_game(seed, 4), reference);
+        }
+    }
+}
@@ -6,7 +6,7 @@ use {
     super::*,
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Action {
     Moves(Dir),
     Aims(Dir),
@@ -15,7 +15,7 @@ pub enum Action {
     Fires(Dir, ActorId),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ActorMove {
     pub actor_id: ActorId,
     pub action: Action,
@@ -35,7 +35,7 @@ impl ActorMove {
 
 /// what the world plays in a non-player turn.
 /// Arrays here must be consistent with the board.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct WorldMove {
     pub actor_moves: Vec<ActorMove>,
 }
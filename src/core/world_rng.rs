@@ -0,0 +1,38 @@
+use {
+    std::time::SystemTime,
+};
+
+/// the state of the pseudo-random choices of the world.
+///
+/// The moves of the world only depend on the board and on
+/// this state, which gives one seed per world turn: two games
+/// started with the same seed and with the same lapin moves
+/// are identical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WorldRng {
+    seed: usize,
+}
+
+impl WorldRng {
+    pub fn new(seed: usize) -> Self {
+        Self { seed }
+    }
+    /// a state initialized from the current time, for
+    /// when reproducibility isn't asked
+    pub fn from_time() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| (d.as_secs()%7) as usize);
+        Self::new(seed)
+    }
+    /// the seed which will be given to the next world turn
+    pub fn seed(self) -> usize {
+        self.seed
+    }
+    /// return the seed for a world turn and advance the state
+    pub fn next_seed(&mut self) -> usize {
+        let seed = self.seed;
+        self.seed = self.seed.wrapping_add(1);
+        seed
+    }
+}
@@ -164,6 +164,9 @@ impl<'d> BoardDrawer<'d> {
             for actor_move in &world_move.actor_moves {
                 let actor_id = actor_move.actor_id;
                 let mut actor = actors.by_id(actor_id);
+                if !self.is_visible(actor.pos) {
+                    continue; // hidden by the fog
+                }
                 match actor_move.action {
                     Action::Moves(dir) => {
                         self.draw_move_step(
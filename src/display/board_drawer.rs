@@ -31,6 +31,7 @@ pub struct BoardDrawer<'d> {
     pub board: &'d Board,
     pub screen: &'d Screen,
     pub pos_converter: PosConverter,
+    pub vision: Option<&'d Vision>, // in fog of war, what the lapin sees
 }
 impl<'d> BoardDrawer<'d> {
 
@@ -40,7 +41,18 @@ impl<'d> BoardDrawer<'d> {
         center: Pos,
     ) -> Self {
         let pos_converter = PosConverter::from(center, screen);
-        Self { board, screen, pos_converter }
+        Self { board, screen, pos_converter, vision: None }
+    }
+
+    /// restrict the drawing to what the lapin sees and remembers
+    pub fn with_vision(mut self, vision: Option<&'d Vision>) -> Self {
+        self.vision = vision;
+        self
+    }
+
+    /// tell whether the actors and items of the cell are shown
+    pub fn is_visible(&self, pos: Pos) -> bool {
+        self.vision.map_or(true, |vision| vision.is_visible(pos))
     }
 
     pub fn draw_chr_bg(
@@ -51,6 +63,9 @@ impl<'d> BoardDrawer<'d> {
         fg_color: Color,
         bg_color: Color,
     ) -> Result<()> {
+        if !self.is_visible(pos) {
+            return Ok(());
+        }
         if let Some(sp) = self.pos_converter.to_screen(pos) {
             let cs = ContentStyle {
                 foreground_color: Some(fg_color),
@@ -86,6 +101,16 @@ impl<'d> BoardDrawer<'d> {
         Ok(())
     }
 
+    /// highlight the cell where the lapin is advised to go
+    pub fn draw_hint(
+        &mut self,
+        con: &mut Context,
+        dir: Dir,
+    ) -> Result<()> {
+        let pos = self.board.lapin_pos().in_dir(dir);
+        self.draw_chr_bg(con, pos, threat_overlay::arrow(dir), Color::White, con.skin.hint)
+    }
+
     pub fn draw(
         &mut self,
         con: &mut Context,
@@ -96,10 +121,27 @@ impl<'d> BoardDrawer<'d> {
         con.w.queue(last_terrain.bg_command(&con.skin))?;
         for j in 0..self.screen.areas.board.height {
             let sy = self.screen.areas.board.top + j;
-            let mut sx = self.screen.areas.board.left;
-            con.w.queue(cursor::MoveTo(sx, sy))?;
-            for _ in 0..self.screen.areas.board.width {
+            let left = self.screen.areas.board.left;
+            con.w.queue(cursor::MoveTo(left, sy))?;
+            for sx in left..left + self.screen.areas.board.width {
                 let pos = self.pos_converter.to_real(ScreenPos::new(sx, sy));
+                if !self.is_visible(pos) {
+                    // unknown cells are dark, remembered ones show a dimmed terrain
+                    let remembered = self.vision.map_or(false, |vision| vision.is_remembered(pos));
+                    let (chr, bg) = if remembered {
+                        ('▓', self.board.get(pos).bg(&con.skin))
+                    } else {
+                        (' ', con.skin.fog)
+                    };
+                    let cs = ContentStyle {
+                        foreground_color: Some(con.skin.fog),
+                        background_color: Some(bg),
+                        attributes: Attributes::default(),
+                    };
+                    con.w.queue(PrintStyledContent(cs.apply(chr)))?;
+                    con.w.queue(last_terrain.bg_command(&con.skin))?;
+                    continue;
+                }
                 let terrain = self.board.get(pos);
                 if terrain != last_terrain {
                     con.w.queue(terrain.bg_command(&con.skin))?;
@@ -114,7 +156,6 @@ impl<'d> BoardDrawer<'d> {
                 } else {
                     con.w.queue(Print(' '))?;
                 }
-                sx += 1;
             }
         }
 
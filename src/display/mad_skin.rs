@@ -31,6 +31,12 @@ pub fn make(skin: &Skin) -> MadSkin {
     carrot.set_bg(bg);
     let mut wine = skin.wine.clone();
     wine.set_bg(bg);
+    let mut key = skin.key.clone();
+    key.set_bg(bg);
+    let mut door = skin.door.clone();
+    door.set_bg(bg);
+    let mut lever = skin.lever.clone();
+    lever.set_bg(bg);
     ms.special_chars.insert(Compound::raw_str("H").code(), skin.hunter.clone());
     ms.special_chars.insert(Compound::raw_str("K").code(), skin.knight.clone());
     ms.special_chars.insert(Compound::raw_str("L").code(), lapin);
@@ -40,8 +46,15 @@ pub fn make(skin: &Skin) -> MadSkin {
     ms.special_chars.insert(Compound::raw_str("D").code(), skin.dragon.clone());
     ms.special_chars.insert(Compound::raw_str("g").code(), Terrain::Grass.bg_as_styled_char(skin));
     ms.special_chars.insert(Compound::raw_str("s").code(), Terrain::Sand.bg_as_styled_char(skin));
+    ms.special_chars.insert(Compound::raw_str("i").code(), Terrain::Ice.bg_as_styled_char(skin));
+    ms.special_chars.insert(Compound::raw_str("b").code(), Terrain::Bush.bg_as_styled_char(skin));
+    ms.special_chars.insert(Compound::raw_str("o").code(), Terrain::Hole.bg_as_styled_char(skin));
+    ms.special_chars.insert(Compound::raw_str("=").code(), Terrain::Bridge.bg_as_styled_char(skin));
     ms.special_chars.insert(Compound::raw_str("c").code(), carrot);
     ms.special_chars.insert(Compound::raw_str("w").code(), wine);
+    ms.special_chars.insert(Compound::raw_str("k").code(), key);
+    ms.special_chars.insert(Compound::raw_str("d").code(), door);
+    ms.special_chars.insert(Compound::raw_str("l").code(), lever);
     ms.italic.set_fg(AnsiValue(178));
     ms.scrollbar.thumb.set_fg(AnsiValue(178));
     ms.inline_code.set_bg(ansi(22));
@@ -13,6 +13,8 @@ pub mod mad_skin;
 mod screen;
 mod skin;
 mod status;
+mod text_box;
+mod threat_overlay;
 
 pub type W = BufWriter<Stderr>;
 
@@ -25,5 +27,6 @@ pub use {
     screen::Screen,
     skin::Skin,
     status::Status,
+    text_box::draw_text_box,
 };
 
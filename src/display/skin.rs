@@ -22,6 +22,13 @@ pub struct Skin {
     pub grass: Color,
     pub water: Color,
     pub sand: Color,
+    pub ice: Color,
+    pub bridge: Color,
+    pub bush: Color,
+    pub hole: Color,
+    pub fog: Color, // cells the lapin never saw, and dimming of remembered ones
+    pub threat: Color, // cells where the lapin would be killed
+    pub hint: Color, // the cell where a hint suggests to go
     // actors
     pub fox: StyledChar,
     pub hunter: StyledChar,
@@ -34,6 +41,9 @@ pub struct Skin {
     // items
     pub carrot: StyledChar,
     pub wine: StyledChar,
+    pub key: StyledChar,
+    pub door: StyledChar,
+    pub lever: StyledChar,
     // special states
     pub aiming_up: char,
     pub aiming_right: char,
@@ -73,6 +83,13 @@ impl Skin {
             grass: ansi(22), // GRASS
             water: ansi(25), // WATER
             sand: ansi(137), // SAND
+            ice: ansi(152), // ICE
+            bridge: ansi(94), // BRIDGE
+            bush: ansi(64), // BUSH
+            hole: gray(1), // HOLE
+            fog: gray(0),
+            threat: ansi(124),
+            hint: ansi(34),
             // actors
             fox: object_char('█', 'F', ansi(166), color_blind),
             hunter: object_char('█', 'H', ansi(58), color_blind),
@@ -90,6 +107,9 @@ impl Skin {
             // items
             carrot: object_char('⬩', 'c', ansi(172), color_blind),
             wine: object_char('w', 'c', ansi(160), color_blind),
+            key: object_char('⚷', 'k', ansi(220), color_blind),
+            door: object_char('▒', 'd', ansi(130), color_blind),
+            lever: object_char('⊸', 'l', ansi(250), color_blind),
             // animations
             hunter_fire_horizontal: StyledChar::from_fg_char(Color::White, '―'),
             hunter_fire_vertical: StyledChar::from_fg_char(Color::White, '│'),
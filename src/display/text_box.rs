@@ -0,0 +1,46 @@
+use {
+    anyhow::Result,
+    crate::{
+        app::Context,
+    },
+    std::io::Write,
+    super::{
+        mad_skin,
+        Screen,
+    },
+    termimad::FmtText,
+};
+
+const MAX_WIDTH: u16 = 60;
+
+/// draw a box, centered over the board, containing a markdown
+/// text (for example the intro of a level)
+pub fn draw_text_box(
+    con: &mut Context,
+    screen: &Screen,
+    markdown: &str,
+) -> Result<()> {
+    let board_area = &screen.areas.board;
+    if board_area.width < 10 || board_area.height < 3 {
+        return Ok(());
+    }
+    let width = MAX_WIDTH.min(board_area.width - 4);
+    let skin = mad_skin::make(&con.skin);
+    // FmtText doesn't fill the end of the lines, so the box
+    // is first filled with blanks, then the text is written over
+    let text = FmtText::from(&skin, markdown, Some(width as usize - 2)).to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    let height = (lines.len() as u16 + 2).min(board_area.height);
+    let left = board_area.left + (board_area.width - width) / 2;
+    let top = board_area.top + (board_area.height - height) / 2;
+    let blank = " ".repeat(width as usize);
+    for y in 0..height {
+        screen.goto(con.w, left, top + y)?;
+        skin.paragraph.compound_style.queue_str(con.w, &blank)?;
+    }
+    for (y, line) in lines.iter().take(height as usize - 2).enumerate() {
+        screen.goto(con.w, left + 1, top + 1 + y as u16)?;
+        write!(con.w, "{}", line)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use {
+    anyhow::Result,
+    crate::{
+        app::Context,
+        core::*,
+        pos::*,
+    },
+    super::*,
+};
+
+pub(super) fn arrow(dir: Dir) -> char {
+    match dir {
+        Dir::Up => '↑',
+        Dir::Right => '→',
+        Dir::Down => '↓',
+        Dir::Left => '←',
+        Dir::UpRight => '↗',
+        Dir::RightDown => '↘',
+        Dir::DownLeft => '↙',
+        Dir::LeftUp => '↖',
+    }
+}
+
+impl<'d> BoardDrawer<'d> {
+    /// draw over the board the cells where the lapin would die,
+    /// the firing lines, and arrows for the planned moves, but
+    /// only the threats of the actors the lapin sees
+    pub fn draw_threats(
+        &mut self,
+        con: &mut Context,
+        threats: &Threats,
+    ) -> Result<()> {
+        for &(pos, killer_id) in &threats.lethal {
+            if !self.is_visible(self.board.actors.by_id(killer_id).pos) {
+                continue;
+            }
+            let (chr, fg) = match self.board.items.get(pos) {
+                Some(item) => {
+                    let sc = item.kind.skin(&con.skin);
+                    (sc.get_char(), sc.get_fg().unwrap_or(con.skin.threat))
+                }
+                None => ('×', con.skin.fog),
+            };
+            self.draw_chr_bg(con, pos, chr, fg, con.skin.threat)?;
+        }
+        for &(pos, dir, firer_id) in &threats.firing_lines {
+            if self.board.actors.has_pos(pos)
+                || !self.is_visible(self.board.actors.by_id(firer_id).pos)
+            {
+                continue;
+            }
+            let sc = if dir.is_vertical() {
+                con.skin.hunter_fire_vertical.clone()
+            } else {
+                con.skin.hunter_fire_horizontal.clone()
+            };
+            self.draw_fg(con, pos, sc)?;
+        }
+        for actor_move in &threats.world_move.actor_moves {
+            let actor = self.board.actors.by_id(actor_move.actor_id);
+            if !self.is_visible(actor.pos) {
+                continue;
+            }
+            if let Action::Moves(dir) | Action::Eats(dir, _) = actor_move.action {
+                let dst = actor.pos.in_dir(dir);
+                if !self.board.actors.has_pos(dst) {
+                    let color = actor.kind.skin(&con.skin).get_fg().unwrap();
+                    self.draw_chr(con, dst, arrow(dir), color)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -4,20 +4,51 @@ use {
         core::*,
         pos::*,
     },
+    serde::{Serialize, Deserialize},
+    std::fmt,
     super::{
         ink::*,
+        stamp::*,
     },
 };
 
 /// an action which may be kept in a stack
 /// for redoing from a saved board state
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum DrawingAction {
     DotInk (Ink, Pos),
     LineInk (Ink, Pos, Pos),
     CompassLineInk (Ink, Pos, Pos), // a line in one of the 8 compass directions
     RectInk (Ink, Pos, Pos),
     DefaultTerrain(Terrain),
+    Link(Pos, Pos), // from a switch to its target
+    Clear(PosArea),
+    Paste(Stamp, Pos), // the top left corner of the stamp is at pos
+    FrameInk (Ink, Pos, Pos), // the outline of a rect
+    EllipseInk (Ink, Pos, Pos), // a filled ellipse in the rect
+    EllipseOutlineInk (Ink, Pos, Pos),
+    FillInk (Ink, Pos, Option<PosArea>), // the optional area limits the fill
+    PathInk (Ink, Vec<Pos>), // a free hand stroke, with lines between the points
+}
+
+impl fmt::Display for DrawingAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrawingAction::DotInk(ink, p) => write!(f, "{} at {},{}", ink, p.x, p.y),
+            DrawingAction::LineInk(ink, ..) => write!(f, "line of {}", ink),
+            DrawingAction::CompassLineInk(ink, ..) => write!(f, "line of {}", ink),
+            DrawingAction::RectInk(ink, ..) => write!(f, "rect of {}", ink),
+            DrawingAction::DefaultTerrain(terrain) => write!(f, "default terrain: {}", terrain),
+            DrawingAction::Link(a, b) => write!(f, "link {},{} to {},{}", a.x, a.y, b.x, b.y),
+            DrawingAction::Clear(area) => write!(f, "cut {}x{}", area.x.len(), area.y.len()),
+            DrawingAction::Paste(stamp, p) => write!(f, "{} at {},{}", stamp.name, p.x, p.y),
+            DrawingAction::FrameInk(ink, ..) => write!(f, "frame of {}", ink),
+            DrawingAction::EllipseInk(ink, ..) => write!(f, "ellipse of {}", ink),
+            DrawingAction::EllipseOutlineInk(ink, ..) => write!(f, "ellipse outline of {}", ink),
+            DrawingAction::FillInk(ink, ..) => write!(f, "fill with {}", ink),
+            DrawingAction::PathInk(ink, ..) => write!(f, "stroke of {}", ink),
+        }
+    }
 }
 
 /// apply a drop of ink at some pos of the board.
@@ -37,6 +68,9 @@ fn ink_pos(ink: Ink, pos: Pos, board: &mut Board) {
         Ink::Item(item_kind) => {
             board.add_item_in(item_kind, pos.x, pos.y);
         }
+        Ink::Link => {
+            // links are made between two clicks, not by drops of ink
+        }
         Ink::EraseActor => {
             board.actors.remove_by_pos(pos);
         }
@@ -60,6 +94,20 @@ fn ink_pos(ink: Ink, pos: Pos, board: &mut Board) {
     }
 }
 
+/// add the link, or remove it if it's already there
+fn toggle_link(switch: Pos, target: Pos, board: &mut Board) {
+    if !board.items.get(switch).is_some_and(|item| item.kind.is_switch()) {
+        debug!("no switch at {:?}", switch);
+        return;
+    }
+    let link = Link::new(switch, target);
+    if let Some(idx) = board.links.iter().position(|&l| l == link) {
+        board.links.remove(idx);
+    } else {
+        board.links.push(link);
+    }
+}
+
 /// draw a line between two pos.
 /// based on Bresenham's algorithm.
 /// Prevents crossing in quadrant dirs (diagonal possible)
@@ -88,6 +136,16 @@ fn ink_line(ink: Ink, a: Pos, b: Pos, board: &mut Board) {
     }
 }
 
+/// draw lines between the successive points of the path
+fn ink_path(ink: Ink, path: &[Pos], board: &mut Board) {
+    if let [pos] = path {
+        ink_pos(ink, *pos, board);
+    }
+    for w in path.windows(2) {
+        ink_line(ink, w[0], w[1], board);
+    }
+}
+
 /// draw a line between two pos, forcing to one of the 8 main directions.
 fn ink_compass_line(ink: Ink, a: Pos, b: Pos, board: &mut Board) {
     use Dir::*;
@@ -130,6 +188,76 @@ fn ink_rect(ink: Ink, a: Pos, b: Pos, board: &mut Board) {
     }
 }
 
+/// draw the outline of a rect given two corners
+fn ink_frame(ink: Ink, a: Pos, b: Pos, board: &mut Board) {
+    let (x0, x1) = (a.x.min(b.x), a.x.max(b.x));
+    let (y0, y1) = (a.y.min(b.y), a.y.max(b.y));
+    for x in x0..=x1 {
+        for y in y0..=y1 {
+            if x == x0 || x == x1 || y == y0 || y == y1 {
+                ink_pos(ink, Pos::new(x, y), board);
+            }
+        }
+    }
+}
+
+/// tell whether the pos is in the ellipse inscribed
+/// in the rect whose two corners are given
+fn in_ellipse(pos: Pos, a: Pos, b: Pos) -> bool {
+    let cx = (a.x + b.x) as f64 / 2.0;
+    let cy = (a.y + b.y) as f64 / 2.0;
+    let rx = (a.x - b.x).abs() as f64 / 2.0 + 0.5;
+    let ry = (a.y - b.y).abs() as f64 / 2.0 + 0.5;
+    let dx = (pos.x as f64 - cx) / rx;
+    let dy = (pos.y as f64 - cy) / ry;
+    dx * dx + dy * dy <= 1.0
+}
+
+/// draw an ellipse given two corners of the rect containing it.
+/// When not filled, only the cells of the ellipse which are
+/// next to a cell out of the ellipse are drawn.
+fn ink_ellipse(ink: Ink, a: Pos, b: Pos, filled: bool, board: &mut Board) {
+    for x in a.x.min(b.x)..=a.x.max(b.x) {
+        for y in a.y.min(b.y)..=a.y.max(b.y) {
+            let pos = Pos::new(x, y);
+            if !in_ellipse(pos, a, b) {
+                continue;
+            }
+            if filled || ALL_DIRS[..4].iter().any(|&dir| !in_ellipse(pos.in_dir(dir), a, b)) {
+                ink_pos(ink, pos, board);
+            }
+        }
+    }
+}
+
+/// apply the ink to the contiguous region of the same
+/// terrain as start, without going out of the limit
+/// or, if there's none, of the level
+fn ink_fill(ink: Ink, start: Pos, limit: Option<&PosArea>, board: &mut Board) {
+    let bound = limit.cloned().unwrap_or_else(|| board.bounding_area());
+    if !bound.contains(start) {
+        return;
+    }
+    let terrain = board.get(start);
+    let mut visited = PosSet::from(bound.clone());
+    visited.insert(start);
+    let mut stack = vec![start];
+    let mut region = Vec::new();
+    while let Some(pos) = stack.pop() {
+        region.push(pos);
+        for &dir in &ALL_DIRS[..4] {
+            let next = pos.in_dir(dir);
+            if bound.contains(next) && !visited.has_key(next) && board.get(next) == terrain {
+                visited.insert(next);
+                stack.push(next);
+            }
+        }
+    }
+    for pos in region {
+        ink_pos(ink, pos, board);
+    }
+}
+
 impl DrawingAction {
     pub fn apply_to(&self, board: &mut Board) {
         time!(Debug, "draw act",
@@ -149,7 +277,101 @@ impl DrawingAction {
             DrawingAction::DefaultTerrain(terrain) => {
                 board.terrains.default = *terrain;
             }
+            DrawingAction::Link(switch, target) => {
+                toggle_link(*switch, *target, board);
+            }
+            DrawingAction::Clear(area) => {
+                clear_area(board, area);
+            }
+            DrawingAction::Paste(stamp, pos) => {
+                stamp.paste(board, *pos);
+            }
+            DrawingAction::FrameInk(ink, a, b) => {
+                ink_frame(*ink, *a, *b, board);
+            }
+            DrawingAction::EllipseInk(ink, a, b) => {
+                ink_ellipse(*ink, *a, *b, true, board);
+            }
+            DrawingAction::EllipseOutlineInk(ink, a, b) => {
+                ink_ellipse(*ink, *a, *b, false, board);
+            }
+            DrawingAction::FillInk(ink, pos, limit) => {
+                ink_fill(*ink, *pos, limit.as_ref(), board);
+            }
+            DrawingAction::PathInk(ink, path) => {
+                ink_path(*ink, path, board);
+            }
         }
         )
     }
 }
+
+#[cfg(test)]
+mod drawing_action_tests {
+
+    use super::*;
+
+    fn assert_grid(board: &Board, expected: &str) {
+        assert_eq!(board.to_ascii(&ascii_area(expected)), normalize_ascii(expected));
+    }
+
+    #[test]
+    fn test_fill_stays_in_region() {
+        let mut board = Board::from_ascii(r#"
+            L..~...
+            ...~...
+            ~~~~...
+        "#).unwrap();
+        DrawingAction::FillInk(Ink::Terrain(Terrain::Sand), Pos::new(1, 1), None).apply_to(&mut board);
+        assert_grid(&board, r#"
+            L::~...
+            :::~...
+            ~~~~...
+        "#);
+        // filling the default terrain stops at the margin of the level
+        DrawingAction::FillInk(Ink::Terrain(Terrain::Grass), Pos::new(-1, 0), None).apply_to(&mut board);
+        let area = PosArea::new(-3..10, -3..6);
+        assert_eq!(board.to_ascii(&area).matches('"').count(), 9 * 5 - 7 * 3);
+        assert_eq!(board.get(Pos::new(-2, -2)), Terrain::Stone);
+        assert_eq!(board.get(Pos::new(8, 3)), Terrain::Stone);
+    }
+
+    #[test]
+    fn test_ellipses() {
+        let mut board = Board::from_ascii(r#"
+            L......
+            .......
+            .......
+            .......
+            .......
+        "#).unwrap();
+        DrawingAction::EllipseOutlineInk(Ink::Terrain(Terrain::Sand), Pos::new(0, 0), Pos::new(6, 4))
+            .apply_to(&mut board);
+        DrawingAction::EllipseInk(Ink::Terrain(Terrain::Water), Pos::new(2, 1), Pos::new(4, 3))
+            .apply_to(&mut board);
+        assert_grid(&board, r#"
+            L:::::.
+            :.~~~.:
+            :.~~~.:
+            :.~~~.:
+            .:::::.
+        "#);
+    }
+
+    #[test]
+    fn test_path_has_no_hole() {
+        let mut board = Board::from_ascii(r#"
+            L......
+            .......
+            .......
+        "#).unwrap();
+        let path = vec![Pos::new(1, 0), Pos::new(4, 0), Pos::new(6, 2)];
+        DrawingAction::PathInk(Ink::Terrain(Terrain::Water), path)
+            .apply_to(&mut board);
+        assert_grid(&board, r#"
+            L~~~~..
+            .....~.
+            ......~
+        "#);
+    }
+}
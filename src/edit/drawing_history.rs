@@ -1,31 +1,107 @@
-
 use {
+    anyhow::Result,
     crate::{
         core::{
             Board,
         },
-        persist::Level,
+        persist::{
+            self,
+            Level,
+            SerdeFormat,
+        },
+        win_db::Signature,
     },
+    serde::{Serialize, Deserialize},
+    std::path::{Path, PathBuf},
     super::{
         drawing_action::*,
     },
 };
 
-#[derive(Debug)]
+/// the number of actions between two checkpoints
+const CHECKPOINT_INTERVAL: usize = 20;
+
+/// the history of the edition of a level, which can be
+/// undone, redone, and saved next to the level file.
+///
+/// Checkpoints (states of the level) are kept every
+/// CHECKPOINT_INTERVAL actions so that going back in
+/// history doesn't need to replay all actions.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DrawingHistory {
+    initial_state: Level,
     actions: Vec<DrawingAction>,
     cursor: usize, // is actions.len() when not in redo
-    initial_state: Level,
+    #[serde(skip)]
+    checkpoints: Vec<Level>, // checkpoints[i] is the state after i*CHECKPOINT_INTERVAL actions
+}
+
+/// the path of the file in which the history of
+/// a level file is saved
+pub fn history_path(level_path: &Path) -> PathBuf {
+    level_path.with_extension("history.json")
+}
+
+/// the level, normalized so that the signature only depends
+/// on what the history changes
+fn comparable_signature(level: &Level) -> Result<Signature> {
+    let mut level = Level::from(&Board::from(level));
+    level.name.clear();
+    level.intro.clear();
+    level.victory.clear();
+    level.objectives.clear();
+    level.fog = false;
+    Signature::new(&level)
 }
 
 impl DrawingHistory {
     pub fn new(level: Level) -> Self {
         Self {
+            checkpoints: vec![level.clone()],
             actions: Vec::new(),
             cursor: 0,
             initial_state: level,
         }
     }
+    /// load the history saved next to the level file, provided
+    /// it ends at the level (if not, it's probably outdated)
+    pub fn load(level_path: &Path, level: &Level) -> Result<Self> {
+        let mut history: Self = persist::read_file(&history_path(level_path))?;
+        if history.cursor > history.actions.len() {
+            return Err(anyhow!("invalid history cursor"));
+        }
+        let mut board = Board::from(&history.initial_state);
+        history.checkpoints = vec![history.initial_state.clone()];
+        let mut state_at_cursor = history.initial_state.clone();
+        for (i, action) in history.actions.iter().enumerate() {
+            action.apply_to(&mut board);
+            if (i + 1) % CHECKPOINT_INTERVAL == 0 {
+                history.checkpoints.push(Level::from(&board));
+            }
+            if i + 1 == history.cursor {
+                state_at_cursor = Level::from(&board);
+            }
+        }
+        if comparable_signature(&state_at_cursor)? != comparable_signature(level)? {
+            return Err(anyhow!("the history doesn't match the level"));
+        }
+        Ok(history)
+    }
+    /// save the history next to the level file
+    pub fn save(&self, level_path: &Path) -> Result<()> {
+        persist::write_file(
+            self,
+            &history_path(level_path),
+            Some(SerdeFormat::Json),
+            false,
+        )
+    }
+    pub fn actions(&self) -> &[DrawingAction] {
+        &self.actions
+    }
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
     pub fn can_redo(&self) -> bool {
         self.cursor < self.actions.len()
     }
@@ -34,34 +110,74 @@ impl DrawingHistory {
     pub fn apply(&mut self, action: DrawingAction, board: &mut Board) {
         if self.can_redo() {
             self.actions.truncate(self.cursor);
+            self.checkpoints.truncate(self.cursor / CHECKPOINT_INTERVAL + 1);
         }
         action.apply_to(board);
         self.actions.push(action);
         self.cursor = self.actions.len();
+        if self.cursor % CHECKPOINT_INTERVAL == 0 {
+            self.checkpoints.push(Level::from(&*board));
+        }
     }
-    /// revert to the state before the current action.
-    /// Return false if nothing changed (i.e. we're already at
-    /// the initial state)
-    pub fn undo(&mut self, board: &mut Board) -> bool {
-        if self.cursor == 0 {
+    /// bring the board to the state after `idx` actions,
+    /// starting from the nearest checkpoint when going back.
+    /// Return false if nothing changed
+    pub fn go_to(&mut self, idx: usize, board: &mut Board) -> bool {
+        if idx == self.cursor || idx > self.actions.len() {
             return false;
         }
-        self.cursor -= 1;
-        board.reset_to(&self.initial_state);
-        for i in 0..self.cursor {
-            self.actions[i].apply_to(board);
+        if idx < self.cursor {
+            let checkpoint = idx / CHECKPOINT_INTERVAL;
+            board.reset_to(&self.checkpoints[checkpoint]);
+            self.cursor = checkpoint * CHECKPOINT_INTERVAL;
         }
+        for action in &self.actions[self.cursor..idx] {
+            action.apply_to(board);
+        }
+        self.cursor = idx;
         true
     }
+    /// revert to the state before the current action.
+    /// Return false if nothing changed (i.e. we're already at
+    /// the initial state)
+    pub fn undo(&mut self, board: &mut Board) -> bool {
+        self.cursor > 0 && self.go_to(self.cursor - 1, board)
+    }
     /// cancel one undo, if possible
     pub fn redo(&mut self, board: &mut Board) -> bool {
-        if !self.can_redo() {
-            return false;
-        }
-        self.actions[self.cursor].apply_to(board);
-        self.cursor += 1;
-        true
+        self.can_redo() && self.go_to(self.cursor + 1, board)
     }
 }
 
+#[cfg(test)]
+mod drawing_history_tests {
+
+    use {
+        super::*,
+        crate::{
+            core::*,
+            edit::ink::Ink,
+            pos::*,
+        },
+    };
 
+    #[test]
+    fn test_going_back_through_checkpoints() {
+        let level = Level::default();
+        let mut board = Board::from(&level);
+        let mut history = DrawingHistory::new(level);
+        let mut states = vec![Level::from(&board)];
+        for i in 0..50 {
+            let action = DrawingAction::DotInk(Ink::Terrain(Terrain::Stone), Pos::new(i, 3));
+            history.apply(action, &mut board);
+            states.push(Level::from(&board));
+        }
+        for &idx in &[45, 20, 19, 0, 33, 50] {
+            assert!(history.go_to(idx, &mut board));
+            assert_eq!(
+                Signature::new(&Level::from(&board)).unwrap(),
+                Signature::new(&states[idx]).unwrap(),
+            );
+        }
+    }
+}
@@ -23,25 +23,83 @@ use {
     },
 };
 
+/// the texts of the level which can be edited in the head panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextField {
+    Name,
+    Intro,
+    Victory,
+}
+
+impl TextField {
+    fn get(self, board: &Board) -> &str {
+        match self {
+            Self::Name => &board.name,
+            Self::Intro => &board.intro,
+            Self::Victory => &board.victory,
+        }
+    }
+    fn set(self, board: &mut Board, content: String) {
+        match self {
+            Self::Name => board.name = content,
+            Self::Intro => board.intro = content,
+            Self::Victory => board.victory = content,
+        }
+    }
+    fn placeholder(self) -> &'static str {
+        match self {
+            Self::Name => "-unnamed level-",
+            Self::Intro | Self::Victory => "-none-",
+        }
+    }
+}
+
 /// display widgets to edit general level stuff:
 /// - name
+/// - intro and victory texts
 /// - default terrain
 pub struct EditorHeadPanel {
     inkwells: Vec<InkWell>,
-    name_area: Area,
-    name_field: Option<InputField>,
+    text_areas: Vec<(TextField, Area)>,
+    field: Option<(TextField, InputField)>,
 }
 
 impl EditorHeadPanel {
 
     pub fn new() -> Self {
         let inkwells = Vec::new(); // this will be filled when drawing
-        let name_area = Area::uninitialized();
+        let text_areas = Vec::new(); // this will be filled when drawing
         Self {
             inkwells,
-            name_area,
-            name_field: None,
+            text_areas,
+            field: None,
+        }
+    }
+
+    /// draw the content of a text, or its input field when
+    /// it's being edited
+    fn draw_text(
+        &self,
+        con: &mut Context,
+        board: &Board,
+        text_field: TextField,
+        area: &Area,
+    ) -> Result<()> {
+        let cs = con.skin.editor.paragraph.compound_style.clone();
+        match &self.field {
+            Some((tf, input_field)) if *tf == text_field => {
+                input_field.display_on(con.w)?;
+            }
+            _ => {
+                let text = text_field.get(board);
+                let text = text.lines().next().unwrap_or("");
+                let text = if text.is_empty() { text_field.placeholder() } else { text };
+                let text: String = text.chars().take(area.width as usize).collect();
+                ScreenPos::new(area.left, area.top).goto(con.w)?;
+                cs.queue_str(con.w, &text)?;
+            }
         }
+        Ok(())
     }
 
     pub fn draw(
@@ -53,10 +111,29 @@ impl EditorHeadPanel {
         let area = &screen.areas.header;
         let cs = con.skin.editor.paragraph.compound_style.clone();
         self.inkwells.clear();
+        self.text_areas.clear();
 
-        // clear first line
+        // first line: intro and victory texts
         screen.goto(con.w, 0, area.top)?;
         cs.clear(con.w, ClearType::UntilNewLine)?;
+        let half_width = area.width / 2;
+        for (i, (text_field, label)) in [
+            (TextField::Intro, " Intro: "),
+            (TextField::Victory, " Victory: "),
+        ].iter().enumerate() {
+            let left = i as u16 * half_width;
+            screen.goto(con.w, left, area.top)?;
+            cs.queue_str(con.w, label)?;
+            let label_width = label.chars().count() as u16;
+            let text_area = Area::new(
+                left + label_width,
+                area.top,
+                half_width.saturating_sub(label_width + 2),
+                1,
+            );
+            self.draw_text(con, board, *text_field, &text_area)?;
+            self.text_areas.push((*text_field, text_area));
+        }
 
         // clear line below inkwells because we'll draw the marks
         screen.goto(con.w, 0, area.top + 2)?;
@@ -78,23 +155,14 @@ impl EditorHeadPanel {
 
         // name
         sp.x += 5;
-        sp.goto(con.w)?;
-        self.name_area = Area::new(
+        let name_area = Area::new(
             sp.x,
             screen.areas.header.top + 1,
             area.width - sp.x - 2,
             1,
         );
-
-        if let Some(name_field) = &self.name_field {
-            name_field.display_on(con.w)?;
-        } else {
-            cs.queue_str(con.w, if board.name.is_empty() {
-                "-unnamed level-"
-            } else {
-                &board.name
-            })?;
-        }
+        self.draw_text(con, board, TextField::Name, &name_area)?;
+        self.text_areas.push((TextField::Name, name_area));
 
         con.w.flush()?;
         Ok(())
@@ -102,12 +170,12 @@ impl EditorHeadPanel {
 
     /// return true when the call effectively closed the field
     /// (ie it was open)
-    pub fn close_name_field(
+    pub fn close_field(
         &mut self,
         board: &mut Board,
     ) -> bool {
-        if let Some(name_field) = self.name_field.take() {
-            board.name = name_field.get_content();
+        if let Some((text_field, input_field)) = self.field.take() {
+            text_field.set(board, input_field.get_content());
             true
         } else {
             false
@@ -115,12 +183,12 @@ impl EditorHeadPanel {
     }
 
     /// tell the panel there was a click outside of it, which
-    /// means the name field should be closed
+    /// means the input field should be closed
     pub fn click_outside(
         &mut self,
         board: &mut Board,
     ) {
-        self.close_name_field(board);
+        self.close_field(board);
     }
 
     /// return true if the event was handled
@@ -130,13 +198,13 @@ impl EditorHeadPanel {
         code: KeyCode,
         board: &mut Board,
     ) -> bool {
-        if let Some(name_field) = &mut self.name_field {
-            if name_field.apply_keycode_event(code) {
+        if let Some((_, input_field)) = &mut self.field {
+            if input_field.apply_keycode_event(code) {
                 true
             } else {
                 match code {
                     KeyCode::Esc | KeyCode::Enter => {
-                        self.close_name_field(board);
+                        self.close_field(board);
                         true
                     }
                     _ => false
@@ -152,16 +220,23 @@ impl EditorHeadPanel {
         sp: ScreenPos,
         board: &mut Board,
     ) -> Option<DrawingAction> {
-        if sp.is_in(&self.name_area) {
-            if let Some(name_field) = &mut self.name_field {
-                name_field.apply_click_event(sp.x, sp.y);
-            } else {
-                let mut field = InputField::new(self.name_area.clone());
-                field.set_content(&board.name);
-                self.name_field = Some(field);
+        let clicked = self.text_areas.iter()
+            .find(|(_, area)| sp.is_in(area))
+            .map(|(text_field, area)| (*text_field, area.clone()));
+        if let Some((text_field, area)) = clicked {
+            match &mut self.field {
+                Some((tf, input_field)) if *tf == text_field => {
+                    input_field.apply_click_event(sp.x, sp.y);
+                }
+                _ => {
+                    self.close_field(board);
+                    let mut input_field = InputField::new(area);
+                    input_field.set_content(text_field.get(board));
+                    self.field = Some((text_field, input_field));
+                }
             }
         } else {
-            self.close_name_field(board);
+            self.close_field(board);
             for inkwell in &self.inkwells {
                 if inkwell.sp == sp {
                     match inkwell.ink {
@@ -0,0 +1,105 @@
+use {
+    anyhow::Result,
+    crate::{
+        app::Context,
+        core::Board,
+        display::Screen,
+        pos::ScreenPos,
+    },
+    std::io::Write,
+    super::{
+        drawing_history::DrawingHistory,
+    },
+    termimad::Area,
+};
+
+const MAX_WIDTH: u16 = 32;
+
+/// a panel, over the right of the board, listing the
+/// past actions. Clicking an action brings the level
+/// back to the state just after it.
+pub struct HistoryPanel {
+    area: Area,
+    lines: Vec<(u16, usize)>, // screen y and history index
+}
+
+impl HistoryPanel {
+
+    pub fn new() -> Self {
+        Self {
+            area: Area::uninitialized(),
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, sp: ScreenPos) -> bool {
+        sp.is_in(&self.area)
+    }
+
+    pub fn draw(
+        &mut self,
+        con: &mut Context,
+        history: &DrawingHistory,
+        screen: &Screen,
+    ) -> Result<()> {
+        let board_area = &screen.areas.board;
+        let width = MAX_WIDTH.min(board_area.width / 2);
+        self.area = Area::new(
+            board_area.left + board_area.width - width,
+            board_area.top,
+            width,
+            board_area.height,
+        );
+        self.lines.clear();
+        let cs = con.skin.editor.paragraph.compound_style.clone();
+        let selected_cs = con.skin.editor.bold.clone();
+        let height = self.area.height as usize;
+        // index 0 is the initial level, index i the state after i actions
+        let count = history.actions().len() + 1;
+        let cursor = history.cursor();
+        let start = if count <= height {
+            0
+        } else {
+            cursor.saturating_sub(height / 2).min(count - height)
+        };
+        for j in 0..height {
+            let y = self.area.top + j as u16;
+            screen.goto(con.w, self.area.left, y)?;
+            let idx = start + j;
+            let label = if idx >= count {
+                String::new()
+            } else if idx == 0 {
+                " initial level".to_string()
+            } else {
+                format!(" {} {}", idx, history.actions()[idx - 1])
+            };
+            let label: String = format!("{:<w$}", label, w = width as usize)
+                .chars()
+                .take(width as usize)
+                .collect();
+            if idx == cursor {
+                selected_cs.queue_str(con.w, &label)?;
+            } else {
+                cs.queue_str(con.w, &label)?;
+            }
+            if idx < count {
+                self.lines.push((y, idx));
+            }
+        }
+        con.w.flush()?;
+        Ok(())
+    }
+
+    /// go to the clicked state, if any. Return true
+    /// when the board changed
+    pub fn click(
+        &self,
+        sp: ScreenPos,
+        history: &mut DrawingHistory,
+        board: &mut Board,
+    ) -> bool {
+        self.lines.iter()
+            .find(|(y, _)| *y == sp.y)
+            .is_some_and(|&(_, idx)| history.go_to(idx, board))
+    }
+}
@@ -2,15 +2,17 @@ use {
     crate::{
         core::*,
     },
+    serde::{Serialize, Deserialize},
     std::fmt,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Ink {
     EraseTerrain,
     Terrain(Terrain),
     EraseItem,
     Item(ItemKind),
+    Link, // links a key to a door or a lever to a terrain
     EraseActor,
     Actor(ActorKind),
 }
@@ -22,11 +24,19 @@ pub static TERRAIN_INKS: &[Ink] = &[
     Ink::Terrain(Terrain::Stone),
     Ink::Terrain(Terrain::Grass),
     Ink::Terrain(Terrain::Water),
+    Ink::Terrain(Terrain::Ice),
+    Ink::Terrain(Terrain::Bridge),
+    Ink::Terrain(Terrain::Bush),
+    Ink::Terrain(Terrain::Hole),
 ];
 pub static ITEM_INKS: &[Ink] = &[
     Ink::EraseItem,
     Ink::Item(ItemKind::Carrot),
     Ink::Item(ItemKind::Wine),
+    Ink::Item(ItemKind::Key),
+    Ink::Item(ItemKind::Door),
+    Ink::Item(ItemKind::Lever),
+    Ink::Link,
 ];
 pub static ACTOR_INKS: &[Ink] = &[
     Ink::EraseActor,
@@ -46,6 +56,7 @@ impl fmt::Display for Ink {
             Ink::Terrain(t) => t.fmt(f),
             Ink::EraseItem => write!(f, "remove item"),
             Ink::Item(ik) => ik.fmt(f),
+            Ink::Link => write!(f, "link (or unlink) a switch and its target"),
             Ink::EraseActor => write!(f, "remove actor"),
             Ink::Actor(ak) => ak.fmt(f),
         }
@@ -26,6 +26,7 @@ pub struct InkWell {
 }
 
 const ERASER_CHAR: char = '╳';
+const LINK_CHAR: char = '⇢';
 
 /// draw ink_well in the cursor position
 fn draw_inkwell(
@@ -54,6 +55,9 @@ fn draw_inkwell(
             //}
             item_skin.queue(con.w)?;
         }
+        Ink::Link => {
+            cs.queue(con.w, LINK_CHAR)?;
+        }
         Ink::EraseActor => {
             cs.queue(con.w, ERASER_CHAR)?;
         }
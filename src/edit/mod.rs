@@ -7,11 +7,14 @@ use {
 mod drawing_action;
 mod drawing_history;
 mod head_panel;
+mod history_panel;
 mod ink;
 mod inkwell;
 mod pen;
 mod pen_panel;
+mod stamp;
 mod state;
+mod validation_panel;
 
 pub use state::LevelEditor;
 
@@ -1,12 +1,16 @@
 use {
     crate::{
-        core::Terrain,
+        core::{
+            Board,
+            Terrain,
+        },
         persist::Level,
         pos::*,
     },
     super::{
         drawing_action::*,
         ink::*,
+        stamp::*,
     },
 };
 
@@ -15,16 +19,51 @@ pub enum PenShape {
     Dot,
     Line,
     Rect,
+    Select, // copy (or cut) an area as stamp
+    Stamp,
+    Ellipse,
+    Fill,
 }
-pub static PEN_SHAPES: &[PenShape] = &[PenShape::Dot, PenShape::Line, PenShape::Rect];
+pub static PEN_SHAPES: &[PenShape] = &[
+    PenShape::Dot,
+    PenShape::Line,
+    PenShape::Rect,
+    PenShape::Ellipse,
+    PenShape::Fill,
+    PenShape::Select,
+    PenShape::Stamp,
+];
 
+/// the buttons changing the current stamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StampTool {
+    Previous,
+    Next,
+    FlipHorizontally,
+    FlipVertically,
+    Rotate,
+    Save,
+}
+pub static STAMP_TOOLS: &[(StampTool, &str)] = &[
+    (StampTool::Previous, "◂"),
+    (StampTool::Next, "▸"),
+    (StampTool::FlipHorizontally, "⇆"),
+    (StampTool::FlipVertically, "⇅"),
+    (StampTool::Rotate, "↻"),
+    (StampTool::Save, "save"),
+];
 
 /// defines what will happen on click on the board
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Pen {
     pub shape: PenShape,
     pub ink: Ink,
     shape_start: Option<Pos>,
+    drag: Vec<Pos>, // the cells crossed since the mouse was pressed
+    drag_ctrl: bool,
+    pub stamp: Option<Stamp>,
+    library: StampLibrary,
+    library_idx: usize,
 }
 
 impl Pen {
@@ -38,6 +77,11 @@ impl Pen {
                 Terrain::Mud
             }),
             shape_start: None,
+            drag: Vec::new(),
+            drag_ctrl: false,
+            stamp: None,
+            library: StampLibrary::load(),
+            library_idx: 0,
         }
     }
 
@@ -47,7 +91,17 @@ impl Pen {
         &mut self,
         click_pos: Pos,
         is_control_click: bool,
+        board: &Board,
+        visible_area: &PosArea,
     ) -> Option<DrawingAction> {
+        if self.ink == Ink::Link {
+            return if let Some(switch) = self.shape_start.take() {
+                Some(DrawingAction::Link(switch, click_pos))
+            } else {
+                self.shape_start = Some(click_pos);
+                None
+            };
+        }
         match self.shape {
             PenShape::Dot => {
                 Some(DrawingAction::DotInk(self.ink, click_pos))
@@ -68,7 +122,11 @@ impl Pen {
             }
             PenShape::Rect => {
                 if let Some(start) = self.shape_start {
-                    let action = DrawingAction::RectInk(self.ink, start, click_pos);
+                    let action = if is_control_click {
+                        DrawingAction::FrameInk(self.ink, start, click_pos)
+                    } else {
+                        DrawingAction::RectInk(self.ink, start, click_pos)
+                    };
                     self.shape_start = None;
                     Some(action)
                 } else {
@@ -76,15 +134,169 @@ impl Pen {
                     None
                 }
             }
+            PenShape::Ellipse => {
+                if let Some(start) = self.shape_start.take() {
+                    Some(if is_control_click {
+                        DrawingAction::EllipseOutlineInk(self.ink, start, click_pos)
+                    } else {
+                        DrawingAction::EllipseInk(self.ink, start, click_pos)
+                    })
+                } else {
+                    self.shape_start = Some(click_pos);
+                    None
+                }
+            }
+            PenShape::Fill => {
+                let limit = if is_control_click {
+                    Some(visible_area.clone())
+                } else {
+                    None
+                };
+                Some(DrawingAction::FillInk(self.ink, click_pos, limit))
+            }
+            PenShape::Select => {
+                if let Some(start) = self.shape_start.take() {
+                    let area = PosArea::new(
+                        start.x.min(click_pos.x)..start.x.max(click_pos.x) + 1,
+                        start.y.min(click_pos.y)..start.y.max(click_pos.y) + 1,
+                    );
+                    self.stamp = Some(Stamp::from_board("selection".to_string(), board, &area));
+                    self.shape = PenShape::Stamp;
+                    if is_control_click {
+                        // the selection is cut, to be moved
+                        Some(DrawingAction::Clear(area))
+                    } else {
+                        None
+                    }
+                } else {
+                    self.shape_start = Some(click_pos);
+                    None
+                }
+            }
+            PenShape::Stamp => {
+                self.stamp.as_ref()
+                    .map(|stamp| DrawingAction::Paste(stamp.clone(), click_pos))
+            }
+        }
+    }
+    /// start a possible drag
+    pub fn press(&mut self, pos: Pos) {
+        self.drag = vec![pos];
+        self.drag_ctrl = false;
+    }
+    pub fn is_pressed(&self) -> bool {
+        !self.drag.is_empty()
+    }
+    fn is_dragging(&self) -> bool {
+        self.drag.len() > 1
+    }
+    /// extend the drag to the pos. Return true if it changed
+    pub fn drag_to(&mut self, pos: Pos, is_control_drag: bool) -> bool {
+        if !self.is_pressed() {
+            return false;
+        }
+        let changed = self.drag_ctrl != is_control_drag || self.drag.last() != Some(&pos);
+        self.drag_ctrl = is_control_drag;
+        if self.drag.last() != Some(&pos) {
+            self.drag.push(pos);
+        }
+        changed
+    }
+    /// the action the current drag would draw if the mouse
+    /// was released now, if the pen draws while dragging
+    pub fn drag_action(&self) -> Option<DrawingAction> {
+        if !self.is_dragging() || self.ink == Ink::Link {
+            return None;
+        }
+        let (ink, ctrl) = (self.ink, self.drag_ctrl);
+        let start = self.drag[0];
+        let end = *self.drag.last()?;
+        match self.shape {
+            PenShape::Dot => Some(DrawingAction::PathInk(ink, self.drag.clone())),
+            PenShape::Line if ctrl => Some(DrawingAction::CompassLineInk(ink, start, end)),
+            PenShape::Line => Some(DrawingAction::LineInk(ink, start, end)),
+            PenShape::Rect if ctrl => Some(DrawingAction::FrameInk(ink, start, end)),
+            PenShape::Rect => Some(DrawingAction::RectInk(ink, start, end)),
+            PenShape::Ellipse if ctrl => Some(DrawingAction::EllipseOutlineInk(ink, start, end)),
+            PenShape::Ellipse => Some(DrawingAction::EllipseInk(ink, start, end)),
+            _ => None,
+        }
+    }
+    /// end the press, and return the drawing action, if any. A
+    /// whole drag makes only one action. When the mouse didn't
+    /// move, it's handled as a click.
+    pub fn release(
+        &mut self,
+        pos: Pos,
+        is_control_click: bool,
+        board: &Board,
+        visible_area: &PosArea,
+    ) -> Option<DrawingAction> {
+        self.drag_to(pos, is_control_click);
+        if !self.is_dragging() {
+            self.drag.clear();
+            return self.click(pos, is_control_click, board, visible_area);
+        }
+        let action = self.drag_action();
+        let start = self.drag[0];
+        self.drag.clear();
+        if action.is_some() {
+            self.shape_start = None;
+            return action;
+        }
+        if self.ink == Ink::Link || self.shape == PenShape::Select {
+            // dragging from a corner to the other one (or from the
+            // switch to the target) is like clicking both
+            self.shape_start = Some(start);
+        }
+        self.click(pos, is_control_click, board, visible_area)
+    }
+    /// apply a button of the stamp tools
+    pub fn use_stamp_tool(&mut self, tool: StampTool) {
+        let nb_stamps = self.library.stamps.len();
+        match tool {
+            StampTool::Previous | StampTool::Next if nb_stamps > 0 => {
+                if self.stamp.is_some() {
+                    self.library_idx = if tool == StampTool::Next {
+                        (self.library_idx + 1) % nb_stamps
+                    } else {
+                        (self.library_idx + nb_stamps - 1) % nb_stamps
+                    };
+                }
+                self.stamp = Some(self.library.stamps[self.library_idx].clone());
+            }
+            StampTool::FlipHorizontally => {
+                self.stamp = self.stamp.as_ref().map(Stamp::flipped_horizontally);
+            }
+            StampTool::FlipVertically => {
+                self.stamp = self.stamp.as_ref().map(Stamp::flipped_vertically);
+            }
+            StampTool::Rotate => {
+                self.stamp = self.stamp.as_ref().map(Stamp::rotated);
+            }
+            StampTool::Save => {
+                if let Some(stamp) = &mut self.stamp {
+                    stamp.name = format!("stamp {}", nb_stamps + 1);
+                    self.library.stamps.push(stamp.clone());
+                    self.library_idx = nb_stamps;
+                    if let Err(e) = self.library.save() {
+                        warn!("error while saving stamps: {:?}", e);
+                    }
+                }
+            }
+            _ => {}
         }
     }
     pub fn set_ink(&mut self, ink: Ink) {
         self.ink = ink;
+        self.shape_start = None;
+        self.drag.clear();
         debug!("new pen ink: {:?}", self.ink);
     }
     pub fn set_shape(&mut self, shape: PenShape) {
         self.shape = shape;
         self.shape_start = None;
+        self.drag.clear();
     }
     pub fn shape_started(&self) -> bool {
         self.shape_start.is_some()
@@ -92,13 +304,39 @@ impl Pen {
     /// return the help to display depending on the state,
     /// if any.
     pub fn status_help(&self) -> Option<String> {
+        if self.ink == Ink::Link {
+            return Some(if self.shape_start.is_some() {
+                "Click the door or the terrain to link to this switch".to_string()
+            } else {
+                "Click a key or a lever".to_string()
+            });
+        }
         match self.shape {
             PenShape::Line if self.shape_start.is_some() => {
                 Some("Click again to draw a line - with the *ctrl* key down to force compass directions".to_string())
             }
             PenShape::Rect if self.shape_start.is_some() => {
-                Some("Click again to draw a rectangle".to_string())
+                Some("Click again to draw a rectangle - with the *ctrl* key down to only draw its outline".to_string())
+            }
+            PenShape::Ellipse if self.shape_start.is_some() => {
+                Some("Click again to draw an ellipse - with the *ctrl* key down to only draw its outline".to_string())
+            }
+            PenShape::Line | PenShape::Rect | PenShape::Ellipse => {
+                Some("Drag, or click two points, to draw the shape".to_string())
+            }
+            PenShape::Fill => {
+                Some("Click to fill an area of same terrain - with the *ctrl* key down to stay in the screen".to_string())
             }
+            PenShape::Select if self.shape_start.is_some() => {
+                Some("Click the opposite corner to copy the area - with the *ctrl* key down to cut it".to_string())
+            }
+            PenShape::Select => {
+                Some("Click a corner of the area to copy".to_string())
+            }
+            PenShape::Stamp => Some(match &self.stamp {
+                Some(stamp) => format!("Click to paste the *{}* stamp", stamp.name),
+                None => "Select an area or choose a stamp with ◂ and ▸".to_string(),
+            }),
             _ => None,
         }
     }
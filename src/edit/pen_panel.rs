@@ -28,41 +28,48 @@ struct ShapeWell {
     shape: PenShape,
 }
 
-pub struct PenPanel<'s> {
-    pen: &'s mut Pen,
-    screen: &'s Screen,
+/// a button changing the current stamp
+#[derive(Debug)]
+struct StampToolWell {
+    area: Area,
+    tool: StampTool,
+}
+
+/// the panel where the pen is chosen. It keeps the wells
+/// of the last drawing, on which clicks are checked
+pub struct PenPanel {
     inkwells: Vec<InkWell>,
     shapewells: Vec<ShapeWell>,
+    stamp_tool_wells: Vec<StampToolWell>,
 }
 
-impl<'s> PenPanel<'s> {
-    pub fn new(
-        pen: &'s mut Pen,
-        screen: &'s Screen,
-    ) -> Self {
-        let inkwells = Vec::new(); // this will be filled when drawing
-        let shapewells = Vec::new(); // this will be filled when drawing
+impl PenPanel {
+    pub fn new() -> Self {
+        // the wells are filled when drawing
         Self {
-            pen,
-            screen,
-            inkwells,
-            shapewells,
+            inkwells: Vec::new(),
+            shapewells: Vec::new(),
+            stamp_tool_wells: Vec::new(),
         }
     }
 
     fn draw_shape_pen_panel(
         &mut self,
         con: &mut Context,
+        pen: &Pen,
+        screen: &Screen,
     ) -> Result<()> {
         let cs = &con.skin.editor.paragraph.compound_style;
-        let area = &self.screen.areas.pen_panel;
-        let width = 8;
-        let x = area.left + area.width - width;
-        let mut y = area.top;
-        for &shape in PEN_SHAPES {
-            self.screen.goto(con.w, x, y)?;
-            if self.pen.shape == shape {
-                if self.pen.shape_started() {
+        let area = &screen.areas.pen_panel;
+        let width = 9;
+        let nb_columns = (PEN_SHAPES.len() as u16 + 2) / 3;
+        for (i, &shape) in PEN_SHAPES.iter().enumerate() {
+            // shapes are in columns of 3
+            let x = area.left + area.width - width * (nb_columns - i as u16 / 3);
+            let y = area.top + i as u16 % 3;
+            screen.goto(con.w, x, y)?;
+            if pen.shape == shape {
+                if pen.shape_started() {
                     cs.queue_str(con.w, "▸▸")?;
                 } else {
                     cs.queue_str(con.w, "▸ ")?;
@@ -75,7 +82,39 @@ impl<'s> PenPanel<'s> {
                 shape,
                 area: Area::new(x, y, width, 1),
             });
-            y += 1;
+        }
+        Ok(())
+    }
+
+    /// draw the buttons changing the stamp on the first line
+    fn draw_stamp_tools(
+        &mut self,
+        con: &mut Context,
+        pen: &Pen,
+        screen: &Screen,
+    ) -> Result<()> {
+        if pen.shape != PenShape::Stamp {
+            return Ok(());
+        }
+        let cs = &con.skin.editor.paragraph.compound_style;
+        let y = screen.areas.pen_panel.top;
+        let mut x = 1;
+        screen.goto(con.w, x, y)?;
+        let label = match &pen.stamp {
+            Some(stamp) => format!("Stamp: {} ", stamp.name),
+            None => "Stamp: ".to_string(),
+        };
+        x += label.chars().count() as u16;
+        cs.queue_str(con.w, &label)?;
+        for &(tool, symbol) in STAMP_TOOLS {
+            let width = symbol.chars().count() as u16;
+            cs.queue_str(con.w, " ")?;
+            cs.queue_str(con.w, symbol)?;
+            self.stamp_tool_wells.push(StampToolWell {
+                tool,
+                area: Area::new(x + 1, y, width, 1),
+            });
+            x += width + 1;
         }
         Ok(())
     }
@@ -83,18 +122,21 @@ impl<'s> PenPanel<'s> {
     pub fn draw(
         &mut self,
         con: &mut Context,
+        pen: &Pen,
+        screen: &Screen,
     ) -> Result<()> {
-        let area = &self.screen.areas.pen_panel;
+        let area = &screen.areas.pen_panel;
         let cs = con.skin.editor.paragraph.compound_style.clone();
         self.inkwells.clear();
         self.shapewells.clear();
+        self.stamp_tool_wells.clear();
 
         // clear first line
-        self.screen.goto(con.w, 0, area.top)?;
+        screen.goto(con.w, 0, area.top)?;
         cs.clear(con.w, ClearType::UntilNewLine)?;
 
         // clear line below inkwells because we'll draw the marks
-        self.screen.goto(con.w, 0, area.top + 2)?;
+        screen.goto(con.w, 0, area.top + 2)?;
         cs.clear(con.w, ClearType::UntilNewLine)?;
 
         // Terrains
@@ -102,36 +144,37 @@ impl<'s> PenPanel<'s> {
         sp.goto(con.w)?;
         self.inkwells.extend(draw_inkwells(
             con,
-            self.screen,
+            screen,
             &mut sp,
             " Terrain:",
             TERRAIN_INKS,
-            self.pen.ink,
+            pen.ink,
         )?);
 
         // Items
         self.inkwells.extend(draw_inkwells(
             con,
-            self.screen,
+            screen,
             &mut sp,
             " Item:",
             ITEM_INKS,
-            self.pen.ink,
+            pen.ink,
         )?);
 
         // Actors
         self.inkwells.extend(draw_inkwells(
             con,
-            self.screen,
+            screen,
             &mut sp,
             " Actor:",
             ACTOR_INKS,
-            self.pen.ink,
+            pen.ink,
         )?);
 
         cs.clear(con.w, ClearType::UntilNewLine)?;
 
-        self.draw_shape_pen_panel(con)?;
+        self.draw_stamp_tools(con, pen, screen)?;
+        self.draw_shape_pen_panel(con, pen, screen)?;
         con.w.flush()?;
         Ok(())
     }
@@ -155,17 +198,23 @@ impl<'s> PenPanel<'s> {
     //    Ok(())
     //}
 
-    pub fn click(&mut self, sp: ScreenPos) {
+    pub fn click(&self, sp: ScreenPos, pen: &mut Pen) {
         debug!("pen_panel click {:?}", sp);
         for shapewell in &self.shapewells {
             if sp.is_in(&shapewell.area) {
-                self.pen.set_shape(shapewell.shape);
+                pen.set_shape(shapewell.shape);
+                return;
+            }
+        }
+        for well in &self.stamp_tool_wells {
+            if sp.is_in(&well.area) {
+                pen.use_stamp_tool(well.tool);
                 return;
             }
         }
         for inkwell in &self.inkwells {
             if inkwell.sp == sp {
-                self.pen.set_ink(inkwell.ink);
+                pen.set_ink(inkwell.ink);
                 return;
             }
         }
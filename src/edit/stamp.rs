@@ -0,0 +1,360 @@
+use {
+    anyhow::Result,
+    crate::{
+        core::*,
+        persist::{
+            self,
+            SerdeFormat,
+        },
+        pos::*,
+    },
+    serde::{Serialize, Deserialize},
+    std::{
+        fs,
+        path::PathBuf,
+    },
+};
+
+/// a rectangular piece of level (terrains, items, actors but
+/// not the lapin, and the links inside) which can be pasted
+/// on the board.
+///
+/// Positions are relative to the top left corner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stamp {
+    pub name: String,
+    pub width: Int,
+    pub height: Int,
+    pub terrains: Vec<Terrain>, // lines then columns
+    pub items: Vec<Located<ItemKind>>,
+    pub actors: Vec<Located<ActorKind>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>, // the links whose both ends are in the stamp
+}
+
+impl Stamp {
+
+    /// copy the area of the board
+    pub fn from_board(name: String, board: &Board, area: &PosArea) -> Self {
+        let mut stamp = Self {
+            name,
+            width: area.x.end - area.x.start,
+            height: area.y.end - area.y.start,
+            terrains: Vec::new(),
+            items: Vec::new(),
+            actors: Vec::new(),
+            links: Vec::new(),
+        };
+        let rel = |pos: Pos| Pos::new(pos.x - area.x.start, pos.y - area.y.start);
+        for y in area.y.clone() {
+            for x in area.x.clone() {
+                let pos = Pos::new(x, y);
+                stamp.terrains.push(board.get(pos));
+                if let Some(item) = board.items.get(pos) {
+                    stamp.items.push(Located::new(rel(pos), item.kind));
+                }
+                match board.actors.by_pos(pos) {
+                    Some(actor) if actor.kind != ActorKind::Lapin => {
+                        stamp.actors.push(Located::new(rel(pos), actor.kind));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        stamp.links = board.links.iter()
+            .filter(|link| area.contains(link.switch) && area.contains(link.target))
+            .map(|link| Link::new(rel(link.switch), rel(link.target)))
+            .collect();
+        stamp
+    }
+
+    /// build a stamp from an ascii grid (see `Board::from_ascii`
+    /// for the glyphs). Actors and items are on mud.
+    pub fn from_ascii(name: &str, grid: &str) -> Result<Self> {
+        let area = ascii_area(grid);
+        let mut stamp = Self {
+            name: name.to_string(),
+            width: area.x.end,
+            height: area.y.end,
+            terrains: Vec::new(),
+            items: Vec::new(),
+            actors: Vec::new(),
+            links: Vec::new(),
+        };
+        for (y, line) in normalize_ascii(grid).lines().enumerate() {
+            for x in 0..stamp.width {
+                let pos = Pos::new(x, y as Int);
+                let c = line.chars().nth(x as usize).unwrap_or('#');
+                let terrain = if let Some(terrain) = terrain_from_char(c) {
+                    terrain
+                } else if let Some(kind) = actor_kind_from_char(c) {
+                    stamp.actors.push(Located::new(pos, kind));
+                    ASCII_UNDER_TERRAIN
+                } else if let Some(kind) = item_kind_from_char(c) {
+                    stamp.items.push(Located::new(pos, kind));
+                    ASCII_UNDER_TERRAIN
+                } else {
+                    return Err(anyhow!("unexpected char {:?} in stamp", c));
+                };
+                stamp.terrains.push(terrain);
+            }
+        }
+        Ok(stamp)
+    }
+
+    /// check the stamp is consistent, as it may have been
+    /// read from a file
+    pub fn check(&self) -> Result<()> {
+        if self.width < 1 || self.height < 1 {
+            return Err(anyhow!("empty stamp {:?}", self.name));
+        }
+        if self.terrains.len() != (self.width * self.height) as usize {
+            return Err(anyhow!(
+                "stamp {:?} has {} terrains instead of {}",
+                self.name,
+                self.terrains.len(),
+                self.width * self.height,
+            ));
+        }
+        let area = self.area_at(Pos::new(0, 0));
+        let outside = self.items.iter().map(|lc| lc.pos)
+            .chain(self.actors.iter().map(|lc| lc.pos))
+            .chain(self.links.iter().flat_map(|link| [link.switch, link.target]))
+            .find(|&pos| !area.contains(pos));
+        if let Some(pos) = outside {
+            return Err(anyhow!("stamp {:?} has content outside at {:?}", self.name, pos));
+        }
+        Ok(())
+    }
+
+    fn terrain(&self, x: Int, y: Int) -> Terrain {
+        self.terrains[(y * self.width + x) as usize]
+    }
+
+    /// build a stamp of same size and content with positions
+    /// changed by the given function
+    fn transformed<F>(&self, width: Int, height: Int, f: F) -> Self
+    where
+        F: Fn(Pos) -> Pos,
+    {
+        let mut terrains = vec![Terrain::Mud; (width * height) as usize];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let p = f(Pos::new(x, y));
+                terrains[(p.y * width + p.x) as usize] = self.terrain(x, y);
+            }
+        }
+        Self {
+            name: self.name.clone(),
+            width,
+            height,
+            terrains,
+            items: self.items.iter().map(|lc| Located::new(f(lc.pos), lc.v)).collect(),
+            actors: self.actors.iter().map(|lc| Located::new(f(lc.pos), lc.v)).collect(),
+            links: self.links.iter().map(|link| Link::new(f(link.switch), f(link.target))).collect(),
+        }
+    }
+
+    pub fn flipped_horizontally(&self) -> Self {
+        let w = self.width;
+        self.transformed(self.width, self.height, |p| Pos::new(w - 1 - p.x, p.y))
+    }
+
+    pub fn flipped_vertically(&self) -> Self {
+        let h = self.height;
+        self.transformed(self.width, self.height, |p| Pos::new(p.x, h - 1 - p.y))
+    }
+
+    /// rotate a quarter turn clockwise
+    pub fn rotated(&self) -> Self {
+        let h = self.height;
+        self.transformed(self.height, self.width, |p| Pos::new(h - 1 - p.y, p.x))
+    }
+
+    /// the area covered when pasting at pos
+    pub fn area_at(&self, pos: Pos) -> PosArea {
+        PosArea::new(pos.x..pos.x + self.width, pos.y..pos.y + self.height)
+    }
+
+    /// paste the stamp with its top left corner at pos, replacing
+    /// everything in the area but the lapin
+    pub fn paste(&self, board: &mut Board, pos: Pos) {
+        clear_area(board, &self.area_at(pos));
+        for y in 0..self.height {
+            for x in 0..self.width {
+                board.set(Pos::new(pos.x + x, pos.y + y), self.terrain(x, y));
+            }
+        }
+        for lc in &self.items {
+            board.add_item_in(lc.v, pos.x + lc.pos.x, pos.y + lc.pos.y);
+        }
+        for lc in &self.actors {
+            let p = Pos::new(pos.x + lc.pos.x, pos.y + lc.pos.y);
+            if p == board.lapin_pos() {
+                continue;
+            }
+            if let Err(e) = board.add_actor_in(lc.v, p.x, p.y) {
+                warn!("err in adding actor: {:?}", e);
+            }
+        }
+        for link in &self.links {
+            board.links.push(Link::new(
+                Pos::new(pos.x + link.switch.x, pos.y + link.switch.y),
+                Pos::new(pos.x + link.target.x, pos.y + link.target.y),
+            ));
+        }
+    }
+}
+
+/// remove terrains, items, actors (but the lapin) and the links
+/// touching the area
+pub fn clear_area(board: &mut Board, area: &PosArea) {
+    board.links.retain(|link| !area.contains(link.switch) && !area.contains(link.target));
+    let lapin_pos = board.lapin_pos();
+    for y in area.y.clone() {
+        for x in area.x.clone() {
+            let pos = Pos::new(x, y);
+            board.terrains.unset(pos);
+            board.items.remove(pos);
+            if pos != lapin_pos {
+                board.actors.remove_by_pos(pos);
+            }
+        }
+    }
+}
+
+/// the stamps of the user, saved in their data directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StampLibrary {
+    pub stamps: Vec<Stamp>,
+}
+
+impl Default for StampLibrary {
+    /// the prefabs
+    fn default() -> Self {
+        let stamps = vec![
+            Stamp::from_ascii("house", r#"
+                #####
+                #...#
+                #.c.#
+                ##.##
+            "#),
+            Stamp::from_ascii("river bend", r#"
+                ~~...
+                ~~~..
+                .~~~~
+                ..~~~
+            "#),
+            Stamp::from_ascii("bridge", r#"
+                .~~~.
+                .===.
+                .~~~.
+            "#),
+            Stamp::from_ascii("fenced meadow", r##"
+                ##.##
+                #"""#
+                #"S"#
+                #####
+            "##),
+        ];
+        let stamps = stamps.into_iter()
+            .filter_map(|stamp| match stamp {
+                Ok(stamp) => Some(stamp),
+                Err(e) => {
+                    warn!("invalid prefab stamp: {:?}", e);
+                    None
+                }
+            })
+            .collect();
+        Self { stamps }
+    }
+}
+
+impl StampLibrary {
+
+    fn path() -> Result<PathBuf> {
+        let dir = dirs::data_local_dir().ok_or(anyhow!("No data local dir"))?;
+        Ok(dir.join("lapin/stamps.json"))
+    }
+
+    /// load the user's library, or the prefabs when there's none
+    pub fn load() -> Self {
+        match Self::path().and_then(|path| persist::read_file::<Self>(&path)) {
+            Ok(mut library) => {
+                library.stamps.retain(|stamp| match stamp.check() {
+                    Ok(()) => true,
+                    Err(e) => {
+                        warn!("ignoring stamp: {:?}", e);
+                        false
+                    }
+                });
+                library
+            }
+            Err(e) => {
+                debug!("no stamp library loaded ({:?}), using prefabs", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        persist::write_file(self, &path, Some(SerdeFormat::Json), true)
+    }
+}
+
+#[cfg(test)]
+mod stamp_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_stamp_transforms() {
+        let stamp = Stamp::from_ascii("test", r#"
+            ~.F
+            ##c
+        "#).unwrap();
+        let mut board = Board::from_ascii(r#"
+            L....
+            .....
+            .....
+        "#).unwrap();
+        stamp.rotated().paste(&mut board, Pos::new(1, 0));
+        let expected = r#"
+            L#~..
+            .#...
+            .cF..
+        "#;
+        assert_eq!(board.to_ascii(&ascii_area(expected)), normalize_ascii(expected));
+        let mut board = Board::from_ascii("L....\n.....").unwrap();
+        stamp.flipped_horizontally().flipped_vertically().paste(&mut board, Pos::new(2, 0));
+        assert_eq!(board.to_ascii(&ascii_area("L....\n.....")), "L.c##\n..F.~\n");
+    }
+
+    #[test]
+    fn test_stamp_links() {
+        let mut board = Board::from_ascii(r#"
+            L.....
+            .kd...
+        "#).unwrap();
+        board.links.push(Link::new(Pos::new(1, 1), Pos::new(2, 1)));
+        let stamp = Stamp::from_board("door".to_string(), &board, &PosArea::new(1..3, 1..2));
+        assert!(stamp.check().is_ok());
+        assert_eq!(stamp.links, vec![Link::new(Pos::new(0, 0), Pos::new(1, 0))]);
+        stamp.flipped_horizontally().paste(&mut board, Pos::new(3, 0));
+        assert!(board.links.contains(&Link::new(Pos::new(4, 0), Pos::new(3, 0))));
+        clear_area(&mut board, &PosArea::new(1..3, 1..2));
+        assert_eq!(board.links, vec![Link::new(Pos::new(4, 0), Pos::new(3, 0))]);
+    }
+
+    #[test]
+    fn test_stamp_check() {
+        let mut stamp = Stamp::from_ascii("test", "~.F\n##c").unwrap();
+        assert!(stamp.check().is_ok());
+        stamp.terrains.pop();
+        assert!(stamp.check().is_err());
+    }
+}
@@ -22,9 +22,12 @@ use {
     },
     crossterm::{
         event::{
+            Event as CrosstermEvent,
             KeyCode,
             KeyEvent,
             KeyModifiers,
+            MouseButton,
+            MouseEvent,
         },
     },
     std::{
@@ -34,17 +37,23 @@ use {
     },
     super::{
         LAYOUT,
-        drawing_history::DrawingHistory,
+        drawing_action::DrawingAction,
+        drawing_history::{
+            history_path,
+            DrawingHistory,
+        },
         pen::Pen,
         pen_panel::PenPanel,
         head_panel::EditorHeadPanel,
+        history_panel::HistoryPanel,
+        validation_panel::ValidationPanel,
     },
     termimad::{
         Event,
     },
 };
 
-const DEFAULT_STATUS: &str = "Use arrows to move, *q* to quit, *s* to save, *t* to test, *u*/*r* to undo/redo";
+const DEFAULT_STATUS: &str = "Use arrows to move, *q* to go back, *s* to save, *t* to test, *u*/*r* to undo/redo, *h* for history, *f* for fog";
 
 pub struct LevelEditor {
     board: Board,
@@ -54,7 +63,12 @@ pub struct LevelEditor {
     center: Pos,    // the pos shown at center of the screen
     history: DrawingHistory,
     head_panel: EditorHeadPanel,
+    pen_panel: PenPanel,
+    history_panel: Option<HistoryPanel>, // shown when some
+    validation_panel: ValidationPanel,
     output_format: Option<SerdeFormat>,
+    level_ruleset: Option<Ruleset>, // the specific rules of the level, which the editor keeps
+    campaign_ruleset: Option<Ruleset>, // when the level is edited from its campaign
 }
 
 impl TryFrom<&EditCommand> for LevelEditor {
@@ -62,7 +76,7 @@ impl TryFrom<&EditCommand> for LevelEditor {
     fn try_from(ec: &EditCommand) -> Result<Self> {
         debug!("opening level editor on {:?}", &ec.path);
         let level = if ec.path.exists() {
-            let mut bag: Bag = persist::read_file(&ec.path)?;
+            let mut bag: Bag = persist::read_bag_file(&ec.path)?;
             if let Some(level) = bag.as_sole_level() {
                 level
             } else {
@@ -78,6 +92,7 @@ impl TryFrom<&EditCommand> for LevelEditor {
             ec.path.to_path_buf(),
             level,
             output_format,
+            None,
         ))
     }
 }
@@ -88,12 +103,22 @@ impl LevelEditor {
         path: PathBuf,
         level: Level,
         output_format: Option<SerdeFormat>,
+        campaign_ruleset: Option<Ruleset>,
     ) -> Self {
         let board = Board::from(&level);
         let status = Status::from_message(DEFAULT_STATUS.to_string());
         let pen = Pen::new_for(&level);
         let center = board.lapin_pos();
-        let history = DrawingHistory::new(level);
+        let level_ruleset = level.ruleset.clone();
+        let validation_panel = ValidationPanel::new(&level, campaign_ruleset.as_ref());
+        let history = if history_path(&path).exists() {
+            DrawingHistory::load(&path, &level).unwrap_or_else(|e| {
+                warn!("edition history not loaded: {:?}", e);
+                DrawingHistory::new(level)
+            })
+        } else {
+            DrawingHistory::new(level)
+        };
         let head_panel = EditorHeadPanel::new();
         Self {
             board,
@@ -103,21 +128,114 @@ impl LevelEditor {
             center,
             history,
             head_panel,
+            pen_panel: PenPanel::new(),
+            history_panel: None,
+            validation_panel,
             output_format,
+            level_ruleset,
+            campaign_ruleset,
+        }
+    }
+
+    /// the edited level, with its own ruleset even when
+    /// it's the default one
+    fn level(&self) -> Level {
+        Level {
+            ruleset: self.level_ruleset.clone(),
+            ..Level::from(&self.board)
         }
     }
 
     fn save_to_file(
         &mut self,
     ) -> Result<()> {
-        let level = Level::from(&self.board);
-        let bag = persist::Bag::from(level);
-        persist::write_file(
+        let bag = persist::Bag::from(self.level());
+        persist::write_bag_file(
             &bag,
             &self.path,
             self.output_format,
             false,
-        )
+        )?;
+        // the history is saved with the level so that it can be
+        // checked against it when reopening the editor
+        if let Err(e) = self.history.save(&self.path) {
+            warn!("error while saving edition history: {:?}", e);
+        }
+        Ok(())
+    }
+
+    /// apply the action, keep it in history, and validate
+    /// the level again
+    fn apply(&mut self, action: DrawingAction) {
+        self.history.apply(action, &mut self.board);
+        self.validation_panel.update(&self.level(), self.campaign_ruleset.as_ref());
+    }
+
+    /// the part of the level which is visible on screen
+    fn visible_area(&self, screen: &Screen) -> PosArea {
+        let pos_converter = PosConverter::from(self.center, screen);
+        let board_area = &screen.areas.board;
+        let mut visible_area = PosArea::from_pos(pos_converter.to_real(
+            ScreenPos::new(board_area.left, board_area.top)
+        ));
+        visible_area.grow_to(pos_converter.to_real(ScreenPos::new(
+            board_area.left + board_area.width - 1,
+            board_area.top + board_area.height - 1,
+        )));
+        visible_area
+    }
+
+    /// the level position at the screen position, if it's in
+    /// the visible part of the board
+    fn board_pos(&self, sp: ScreenPos, screen: &Screen) -> Option<Pos> {
+        if !sp.is_in(&screen.areas.board) {
+            return None;
+        }
+        if self.history_panel.as_ref().is_some_and(|hp| hp.contains(sp)) {
+            return None;
+        }
+        Some(PosConverter::from(self.center, screen).to_real(sp))
+    }
+
+    /// handle mouse presses, drags and releases on the board.
+    /// Return false when the event isn't a part of a drag
+    fn handle_mouse_event(&mut self, me: MouseEvent, screen: &Screen) -> bool {
+        match me {
+            MouseEvent::Down(MouseButton::Left, x, y, _) => {
+                match self.board_pos(ScreenPos{ x, y }, screen) {
+                    Some(pos) => {
+                        self.pen.press(pos);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            MouseEvent::Drag(MouseButton::Left, x, y, modifiers) if self.pen.is_pressed() => {
+                let pos = PosConverter::from(self.center, screen).to_real(ScreenPos{ x, y });
+                self.pen.drag_to(pos, modifiers.contains(KeyModifiers::CONTROL));
+                true
+            }
+            MouseEvent::Up(MouseButton::Left, x, y, modifiers) if self.pen.is_pressed() => {
+                let pos = PosConverter::from(self.center, screen).to_real(ScreenPos{ x, y });
+                debug!("release in {:?}", pos);
+                self.head_panel.click_outside(&mut self.board);
+                let visible_area = self.visible_area(screen);
+                let action = self.pen.release(
+                    pos,
+                    modifiers.contains(KeyModifiers::CONTROL),
+                    &self.board,
+                    &visible_area,
+                );
+                if let Some(action) = action {
+                    self.apply(action);
+                }
+                self.status = Status::from_message(
+                    self.pen.status_help().unwrap_or(DEFAULT_STATUS.to_string())
+                );
+                true
+            }
+            _ => false,
+        }
     }
 
     fn handle_key_event(
@@ -149,11 +267,31 @@ impl LevelEditor {
                 self.center = self.board.lapin_pos();
                 None
             }
+            KeyCode::Char('f') | KeyCode::Char('F') => {
+                self.board.fog = !self.board.fog;
+                self.status = Status::from_message(
+                    if self.board.fog {
+                        "fog of war: the lapin only sees what's in its line of sight"
+                    } else {
+                        "no fog of war"
+                    }.to_string()
+                );
+                None
+            }
+            KeyCode::Char('h') | KeyCode::Char('H') => {
+                self.history_panel = match self.history_panel {
+                    Some(_) => None,
+                    None => Some(HistoryPanel::new()),
+                };
+                None
+            }
             KeyCode::Char('q') | KeyCode::Char('Q') => {
-                Some(StateTransition::Quit)
+                Some(StateTransition::Back)
             }
             KeyCode::Char('r') | KeyCode::Char('R') => {
-                self.history.redo(&mut self.board);
+                if self.history.redo(&mut self.board) {
+                    self.validation_panel.update(&self.level(), self.campaign_ruleset.as_ref());
+                }
                 None
             }
             KeyCode::Char('s') | KeyCode::Char('S') => {
@@ -170,7 +308,9 @@ impl LevelEditor {
             }
             KeyCode::Char('t') | KeyCode::Char('T') => Some(StateTransition::PlayLevel{ level_idx: 0 }),
             KeyCode::Char('u') | KeyCode::Char('U') => {
-                self.history.undo(&mut self.board);
+                if self.history.undo(&mut self.board) {
+                    self.validation_panel.update(&self.level(), self.campaign_ruleset.as_ref());
+                }
                 None
             }
             _ => None,
@@ -191,15 +331,33 @@ impl State for LevelEditor {
     ) -> Result<StateTransition> {
         let mut screen = Screen::new(LAYOUT);
         loop {
-            let mut bd = BoardDrawer::new(&self.board, &screen, self.center);
+            // while dragging, the shape is previewed on a copy of the board
+            let preview = self.pen.drag_action().map(|action| {
+                let mut board = self.board.clone();
+                action.apply_to(&mut board);
+                board
+            });
+            let mut bd = BoardDrawer::new(preview.as_ref().unwrap_or(&self.board), &screen, self.center);
             bd.draw(con)?;
-            let mut pen_panel = PenPanel::new(&mut self.pen, &screen);
-            pen_panel.draw(con)?;
+            self.pen_panel.draw(con, &self.pen, &screen)?;
             self.head_panel.draw(con, &self.board, &screen)?;
+            self.validation_panel.draw(con, &screen)?;
+            if let Some(history_panel) = &mut self.history_panel {
+                history_panel.draw(con, &self.history, &screen)?;
+            }
             self.status.display(con, &screen)?;
             con.w.flush()?;
-            let event = con.dam.next_event().unwrap();
+            let dam_event = con.dam.next_dam_event().unwrap();
             con.dam.unblock();
+            if let CrosstermEvent::Mouse(me) = dam_event.raw {
+                if self.handle_mouse_event(me, &screen) {
+                    continue;
+                }
+            }
+            let event = match dam_event.event {
+                Some(event) => event,
+                None => continue,
+            };
             let next_state = match event {
                 Event::Key(KeyEvent { code, .. }) => {
                     self.handle_key_event(code)
@@ -215,21 +373,28 @@ impl State for LevelEditor {
                         self.head_panel.click(sp, &mut self.board)
                     } else {
                         self.head_panel.click_outside(&mut self.board);
-                        if sp.is_in(&screen.areas.board) {
-                            let pos_converter = PosConverter::from(self.center, &screen);
+                        if let Some(history_panel) = self.history_panel.as_ref().filter(|hp| hp.contains(sp)) {
+                            if history_panel.click(sp, &mut self.history, &mut self.board) {
+                                self.validation_panel.update(&self.level(), self.campaign_ruleset.as_ref());
+                            }
+                            None
+                        } else if let Some(pos) = self.board_pos(sp, &screen) {
+                            let visible_area = self.visible_area(&screen);
                             self.pen.click(
-                                pos_converter.to_real(sp),
+                                pos,
                                 modifiers.contains(KeyModifiers::CONTROL),
+                                &self.board,
+                                &visible_area,
                             )
                         } else if sp.is_in(&screen.areas.pen_panel) {
-                            pen_panel.click(sp);
+                            self.pen_panel.click(sp, &mut self.pen);
                             None
                         } else {
                             None
                         }
                     };
                     if let Some(action) = action {
-                        self.history.apply(action, &mut self.board);
+                        self.apply(action);
                     }
                     self.status = Status::from_message(
                         self.pen.status_help().unwrap_or(DEFAULT_STATUS.to_string())
@@ -251,7 +416,23 @@ impl State for LevelEditor {
         &self,
         _level_idx: usize,
     ) -> Option<Level> {
-        Some(Level::from(&self.board))
+        Some(self.level())
+    }
+
+    /// the level is tested with the rules of its campaign
+    fn get_ruleset(
+        &self,
+        _level_idx: usize,
+    ) -> Option<Ruleset> {
+        self.campaign_ruleset.clone()
+    }
+
+    /// the level being edited isn't the one which will be played
+    fn records_games(
+        &self,
+        _level_idx: usize,
+    ) -> bool {
+        false
     }
 
 }
@@ -0,0 +1,88 @@
+use {
+    anyhow::Result,
+    crate::{
+        app::Context,
+        core::{
+            Board,
+            Ruleset,
+        },
+        display::Screen,
+        persist::Level,
+        validate::{
+            self,
+            LevelIssue,
+        },
+    },
+    std::io::Write,
+    termimad::Area,
+};
+
+const MAX_WIDTH: u16 = 56;
+
+/// a panel, over the bottom left of the board, listing the
+/// probable mistakes of the level. It's empty (and not drawn)
+/// when the validator finds nothing.
+pub struct ValidationPanel {
+    issues: Vec<LevelIssue>,
+}
+
+impl ValidationPanel {
+
+    /// validate the level, with the ruleset of the campaign
+    /// if the level doesn't have its own one
+    pub fn new(
+        level: &Level,
+        campaign_ruleset: Option<&Ruleset>,
+    ) -> Self {
+        let mut panel = Self { issues: Vec::new() };
+        panel.update(level, campaign_ruleset);
+        panel
+    }
+
+    /// validate the level again, after a change
+    pub fn update(
+        &mut self,
+        level: &Level,
+        campaign_ruleset: Option<&Ruleset>,
+    ) {
+        let mut board = Board::from(level);
+        board.apply_campaign_ruleset(level, campaign_ruleset);
+        self.issues = validate::validate(&board);
+    }
+
+    pub fn draw(
+        &self,
+        con: &mut Context,
+        screen: &Screen,
+    ) -> Result<()> {
+        if self.issues.is_empty() {
+            return Ok(());
+        }
+        let board_area = &screen.areas.board;
+        let width = MAX_WIDTH.min(board_area.width / 2);
+        let max_height = (board_area.height / 3).max(1) as usize;
+        let height = self.issues.len().min(max_height);
+        let area = Area::new(
+            board_area.left,
+            board_area.top + board_area.height - height as u16,
+            width,
+            height as u16,
+        );
+        let cs = con.skin.error_status.paragraph.compound_style.clone();
+        for j in 0..height {
+            let label = if j + 1 == height && height < self.issues.len() {
+                format!(" ⚠ and {} more issues", self.issues.len() - j)
+            } else {
+                format!(" ⚠ {}", self.issues[j])
+            };
+            let label: String = format!("{:<w$}", label, w = width as usize)
+                .chars()
+                .take(width as usize)
+                .collect();
+            screen.goto(con.w, area.left, area.top + j as u16)?;
+            cs.queue_str(con.w, &label)?;
+        }
+        con.w.flush()?;
+        Ok(())
+    }
+}
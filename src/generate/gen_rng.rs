@@ -0,0 +1,27 @@
+/// a small deterministic pseudo-random generator (splitmix64),
+/// so that a seed always gives the same level
+#[derive(Debug, Clone, Copy)]
+pub struct GenRng {
+    state: u64,
+}
+
+impl GenRng {
+    pub fn new(seed: usize) -> Self {
+        Self { state: seed as u64 }
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    /// a number in [0, n[ (n must not be 0)
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+    /// a number in the range, which must not be empty
+    pub fn in_range(&mut self, range: std::ops::Range<i32>) -> i32 {
+        range.start + self.below((range.end - range.start) as usize) as i32
+    }
+}
@@ -0,0 +1,225 @@
+use {
+    anyhow::Result,
+    crate::{
+        core::*,
+        persist::Level,
+        pos::*,
+        solve::{
+            Outcome,
+            Solver,
+        },
+        stats::LevelStats,
+        validate,
+    },
+    std::time::Duration,
+    super::GenRng,
+};
+
+static DIRS: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+/// the minimal distance between the lapin and an enemy
+const MIN_ENEMY_DISTANCE: Int = 5;
+
+/// what the generated levels should look like
+#[derive(Debug, Clone)]
+pub struct GeneratorParams {
+    pub width: Int,
+    pub height: Int,
+    /// the percentage of cells of each terrain, the rest being mud
+    pub terrains: Vec<(Terrain, usize)>,
+    /// the max number of enemies of each kind
+    pub enemies: Vec<(ActorKind, usize)>,
+    /// the difficulty (as estimated by `LevelStats`) we'd like
+    pub difficulty: Option<f32>,
+}
+
+impl Default for GeneratorParams {
+    fn default() -> Self {
+        Self {
+            width: 24,
+            height: 14,
+            terrains: vec![
+                (Terrain::Stone, 12),
+                (Terrain::Water, 8),
+                (Terrain::Sand, 4),
+                (Terrain::Bush, 4),
+            ],
+            enemies: vec![
+                (ActorKind::Fox, 3),
+                (ActorKind::Wolf, 1),
+                (ActorKind::Hunter, 1),
+            ],
+            difficulty: None,
+        }
+    }
+}
+
+/// a builder of random levels, all valid and winnable
+pub struct Generator {
+    pub params: GeneratorParams,
+    pub seed: usize,
+    /// the number of levels built and checked before we give up
+    pub max_attempts: usize,
+    /// the time the solver may spend checking one level
+    pub max_solve_duration: Duration,
+}
+
+/// a level being built
+struct Draft {
+    area: PosArea,
+    terrains: PosMap<Terrain>,
+    actors: Vec<Actor>,
+}
+
+impl Draft {
+    fn is_free(&self, pos: Pos) -> bool {
+        !self.actors.iter().any(|a| a.pos == pos)
+    }
+    fn random_pos(&self, rng: &mut GenRng, x: std::ops::Range<Int>) -> Pos {
+        Pos::new(rng.in_range(x), rng.in_range(self.area.y.clone()))
+    }
+    /// paint a blob of about `size` cells, as a random walk
+    fn add_blob(&mut self, rng: &mut GenRng, terrain: Terrain, size: usize) {
+        let mut pos = self.random_pos(rng, self.area.x.clone());
+        for _ in 0..size {
+            self.terrains.set(pos, terrain);
+            let next = pos.in_dir(DIRS[rng.below(4)]);
+            if self.area.contains(next) {
+                pos = next;
+            }
+        }
+    }
+    fn into_level(self, name: String) -> Level {
+        let mut terrains: Vec<Located<Terrain>> = self.terrains.iter()
+            .filter(|lc| lc.v != Terrain::Stone)
+            .collect();
+        terrains.sort_by_key(|lc| (lc.pos.y, lc.pos.x));
+        // the enemies are sorted too, so that the level written
+        // in the text format plays the same when read back
+        let mut actors = self.actors;
+        actors[1..].sort_by_key(|a| (a.pos.y, a.pos.x));
+        Level {
+            name,
+            default_terrain: Terrain::Stone,
+            terrains,
+            actors,
+            ..Level::default()
+        }
+    }
+}
+
+impl Generator {
+
+    pub fn new(seed: usize, params: GeneratorParams) -> Self {
+        Self {
+            params,
+            seed,
+            max_attempts: 40,
+            max_solve_duration: Duration::from_secs(10),
+        }
+    }
+
+    /// build a level, without checking it
+    fn draft(&self, rng: &mut GenRng) -> Level {
+        let (width, height) = (self.params.width.max(8), self.params.height.max(5));
+        let area = PosArea::new(0..width, 0..height);
+        let mut draft = Draft {
+            area: area.clone(),
+            terrains: PosMap::new(area, Terrain::Mud),
+            actors: Vec::new(),
+        };
+        let nb_cells = (width * height) as usize;
+        for &(terrain, percent) in &self.params.terrains {
+            let mut remaining = nb_cells * percent / 100;
+            while remaining > 0 {
+                let size = (3 + rng.below(8)).min(remaining);
+                draft.add_blob(rng, terrain, size);
+                remaining -= size;
+            }
+        }
+        // the lapin starts on the left, the grass is on the right
+        let lapin_pos = draft.random_pos(rng, 0..(width / 5).max(1));
+        draft.terrains.set(lapin_pos, Terrain::Mud);
+        draft.actors.push(Actor::new(ActorKind::Lapin, lapin_pos.x, lapin_pos.y));
+        let grass_pos = draft.random_pos(rng, width * 4 / 5..width - 1);
+        for (dx, dy) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+            draft.terrains.set(Pos::new(grass_pos.x + dx, grass_pos.y + dy), Terrain::Grass);
+        }
+        let ruleset = Ruleset::default();
+        for &(kind, budget) in &self.params.enemies {
+            let count = budget - rng.below(budget / 2 + 1);
+            let mut placed = 0;
+            for _ in 0..count * 20 {
+                if placed == count {
+                    break;
+                }
+                let pos = draft.random_pos(rng, draft.area.x.clone());
+                let actor = Actor::new(kind, pos.x, pos.y);
+                if draft.is_free(pos)
+                    && ruleset.can_enter(actor, draft.terrains.get(pos))
+                    && Pos::manhattan_distance(pos, lapin_pos) >= MIN_ENEMY_DISTANCE
+                {
+                    draft.actors.push(actor);
+                    placed += 1;
+                }
+            }
+        }
+        draft.into_level(format!("Random {}", self.seed))
+    }
+
+    /// build levels until one is valid, winnable, and close
+    /// enough to the wanted difficulty. When no level has the
+    /// right difficulty, the nearest one is returned.
+    pub fn generate(&self) -> Result<Level> {
+        let mut rng = GenRng::new(self.seed);
+        let mut solver = Solver::new(self.seed);
+        solver.max_depth = 60;
+        solver.max_states = 10_000;
+        solver.max_duration = self.max_solve_duration;
+        let mut best: Option<(Level, f32)> = None; // level and distance to target
+        for attempt in 0..self.max_attempts {
+            let level = self.draft(&mut rng);
+            if !validate::validate_level(&level).is_empty() {
+                continue;
+            }
+            if !matches!(solver.solve_level(&level).outcome, Outcome::Winnable(_)) {
+                continue;
+            }
+            let difficulty = LevelStats::compute(&level).difficulty;
+            let distance = self.params.difficulty.map_or(0.0, |d| (d - difficulty).abs());
+            debug!("attempt {}: difficulty {}", attempt, difficulty);
+            if best.as_ref().map_or(true, |(_, best_distance)| distance < *best_distance) {
+                best = Some((level, distance));
+            }
+            if distance < 1.0 {
+                break;
+            }
+        }
+        best.map(|(level, _)| level)
+            .ok_or_else(|| anyhow!("no valid level found in {} attempts", self.max_attempts))
+    }
+}
+
+#[cfg(test)]
+mod generator_tests {
+
+    use {
+        super::*,
+        crate::win_db::Signature,
+    };
+
+    #[test]
+    fn test_generation_is_reproducible() {
+        let params = GeneratorParams {
+            width: 12,
+            height: 6,
+            enemies: vec![(ActorKind::Fox, 1)],
+            ..GeneratorParams::default()
+        };
+        let generator = Generator::new(3, params);
+        let a = generator.generate().unwrap();
+        let b = generator.generate().unwrap();
+        assert_eq!(Signature::new(&a).unwrap(), Signature::new(&b).unwrap());
+        assert!(validate::validate_level(&a).is_empty());
+    }
+}
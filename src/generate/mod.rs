@@ -0,0 +1,72 @@
+//! build random levels, without TUI
+
+use {
+    anyhow::Result,
+    crate::{
+        app::GenerateCommand,
+        core::ActorKind,
+        persist::{
+            self,
+            Bag,
+            SerdeFormat,
+        },
+    },
+};
+
+mod gen_rng;
+mod generator;
+
+pub use {
+    gen_rng::GenRng,
+    generator::*,
+};
+
+/// parse a list like "water=10,sand=5", the names being
+/// looked up as in the text format of levels
+fn parse_counts<T>(
+    s: &str,
+    by_name: fn(&str) -> Result<T>,
+) -> Result<Vec<(T, usize)>> {
+    let mut counts = Vec::new();
+    for token in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let (name, count) = match token.split_once('=') {
+            Some((name, count)) => (name.trim(), count.trim()),
+            None => return Err(anyhow!("expected name=count, got {:?}", token)),
+        };
+        counts.push((by_name(name)?, count.parse()?));
+    }
+    Ok(counts)
+}
+
+/// generate a level according to the command and write it
+pub fn run(gc: &GenerateCommand) -> Result<()> {
+    let mut params = GeneratorParams::default();
+    if let Some(width) = gc.width {
+        params.width = width;
+    }
+    if let Some(height) = gc.height {
+        params.height = height;
+    }
+    if let Some(terrains) = &gc.terrains {
+        params.terrains = parse_counts(terrains, persist::terrain_by_name)?;
+    }
+    if let Some(enemies) = &gc.enemies {
+        params.enemies = parse_counts(enemies, persist::actor_kind_by_name)?
+            .into_iter()
+            .filter(|(kind, _)| *kind != ActorKind::Lapin)
+            .collect();
+    }
+    params.difficulty = gc.difficulty;
+    let seed = gc.seed.unwrap_or(0);
+    let level = Generator::new(seed, params).generate()?;
+    let format = gc.output_format.as_ref()
+        .and_then(|key| SerdeFormat::from_key(key));
+    persist::write_bag_file(
+        &Bag::from(level),
+        &gc.path,
+        format,
+        false,
+    )?;
+    println!("level written in {:?}", &gc.path);
+    Ok(())
+}
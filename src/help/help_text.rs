@@ -30,6 +30,15 @@ Here's if you want to know more:
 You don't have to play fast,
 hitting keys frenetically doesn't help
 
+If you made a mistake, hit *u* to undo your
+last move, or *r* to restart the level
+
+Hit *t* to see what the animals will do:
+arrows show their next moves, lines their
+aims, and red cells where you'd be killed
+
+Stuck? Hit *h* for a hint
+
 You'll meet some sheep `S`. They're cool
 But they take too much space on the grass
 so they can be a problem
@@ -37,6 +46,19 @@ so they can be a problem
 Your friend the pink knight `K` is very heavy
 he can't walk on sand `s`
 
+On ice `i` you slide until you hit something
+but the other animals don't slide
+
+Bushes `b` hide you from the hunters
+and only a rabbit can go down a hole `o`
+
+Bridges `=` let everybody cross the water
+and the swimmers pass under them
+
+Pick keys `k` to open the doors `d`
+and pull levers `l` to raise or lower
+bridges and walls
+
 You may find carrots `c`
 When you eat them you can replay
 which might help you escape
@@ -12,13 +12,20 @@ mod time;
 
 pub mod app;
 pub mod campaign;
+pub mod campaign_edit;
 pub mod core;
 pub mod choose;
 pub mod display;
 pub mod edit;
+pub mod generate;
 pub mod help;
 pub mod included;
 pub mod play;
 pub mod pos;
 pub mod persist;
+pub mod profile;
+pub mod replay;
+pub mod solve;
+pub mod stats;
+pub mod validate;
 pub mod win_db;
@@ -16,6 +16,9 @@ use {
     lapin::{
         app::*,
         campaign,
+        generate,
+        solve,
+        stats,
     },
     log::LevelFilter,
     simplelog,
@@ -62,6 +65,10 @@ fn do_campaign_command(cc: &CampaignCommand) -> Result<()> {
     match &cc.sub {
         CampaignSubCommand::New(ncc) => campaign::create(ncc),
         CampaignSubCommand::Pack(pcc) => campaign::pack(pcc),
+        CampaignSubCommand::Unpack(ucc) => campaign::unpack(ucc),
+        CampaignSubCommand::List(lcc) => campaign::list(lcc),
+        CampaignSubCommand::Info(icc) => campaign::info(icc),
+        CampaignSubCommand::Check(ccc) => campaign::check(ccc),
     }
 }
 
@@ -87,6 +94,9 @@ fn main() {
     debug!("fromage: {:?}", &fromage);
     let r = match &fromage.command {
         Some(Command::Campaign(cc)) => do_campaign_command(cc),
+        Some(Command::Solve(sc)) => solve::run(sc),
+        Some(Command::Stats(sc)) => stats::run(sc),
+        Some(Command::Generate(gc)) => generate::run(gc),
         _ => do_tui_command(fromage),
     };
     if let Err(e) = r {
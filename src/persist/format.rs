@@ -1,19 +1,31 @@
 
+use {
+    std::path::{
+        Path,
+        PathBuf,
+    },
+};
 
 /// Formats usable for writing (and reading) levels.
 ///
-/// Right now only JSON and Message Pack seem to make sense.
-/// If another one appears to be desirable I can put it behind
-/// a feature flag.
+/// Right now only JSON and Message Pack seem to make sense
+/// for any data. If another one appears to be desirable I can
+/// put it behind a feature flag.
+///
+/// The Text format is only usable for single levels. Its files
+/// have a double extension (`.lapin.txt`) so that other text
+/// files aren't taken for levels.
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum SerdeFormat {
     Json,
     MessagePack,
+    Text,
 }
 
 pub static FORMATS: &[SerdeFormat] = &[
     SerdeFormat::Json,
     SerdeFormat::MessagePack,
+    SerdeFormat::Text,
 ];
 
 impl SerdeFormat {
@@ -21,15 +33,47 @@ impl SerdeFormat {
         match self {
             Self::Json => "json",
             Self::MessagePack => "mpack",
+            Self::Text => "txt",
         }
     }
     pub fn from_key(key: &str) -> Option<Self> {
         match key {
             "json" => Some(SerdeFormat::Json),
             "mpack" => Some(SerdeFormat::MessagePack),
+            "txt" => Some(SerdeFormat::Text),
             _ => None,
         }
     }
+    /// the extension of the files written in this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::MessagePack => "mpack",
+            Self::Text => "lapin.txt",
+        }
+    }
+    /// guess the format of a file from its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        FORMATS.iter()
+            .find(|f| name.ends_with(&format!(".{}", f.extension())))
+            .copied()
+    }
+    /// the path of a file of this format, with the same
+    /// name as the given one but not its extension
+    pub fn path(self, path: &Path) -> PathBuf {
+        let stem = match Self::from_path(path) {
+            Some(format) => {
+                let name = path.file_name().unwrap().to_string_lossy();
+                path.with_file_name(&name[..name.len() - format.extension().len() - 1])
+            }
+            None => path.with_extension(""),
+        };
+        let mut name = stem.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(self.extension());
+        stem.with_file_name(name)
+    }
 }
 
 impl Default for SerdeFormat {
@@ -38,6 +82,32 @@ impl Default for SerdeFormat {
     }
 }
 
+#[cfg(test)]
+mod format_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_format_paths() {
+        assert_eq!(SerdeFormat::from_path(Path::new("a/b.lapin.txt")), Some(SerdeFormat::Text));
+        assert_eq!(SerdeFormat::from_path(Path::new("a/notes.txt")), None);
+        assert_eq!(SerdeFormat::from_path(Path::new("b.json")), Some(SerdeFormat::Json));
+        assert_eq!(
+            SerdeFormat::Text.path(Path::new("a/b.json")),
+            PathBuf::from("a/b.lapin.txt"),
+        );
+        assert_eq!(
+            SerdeFormat::Json.path(Path::new("a/b.lapin.txt")),
+            PathBuf::from("a/b.json"),
+        );
+        assert_eq!(
+            SerdeFormat::Text.path(Path::new("b.lapin.txt")),
+            PathBuf::from("b.lapin.txt"),
+        );
+        assert_eq!(SerdeFormat::MessagePack.path(Path::new("b")), PathBuf::from("b.mpack"));
+    }
+}
+
 
 // Study of different formats:
 // sizes below for the test level as of 2020/02/28
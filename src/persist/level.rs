@@ -15,6 +15,32 @@ pub struct Level {
     pub terrains: Vec<Located<Terrain>>,
     pub actors: Vec<Actor>, // actors[0] must be the Lapin
     pub items: Vec<Located<Item>>,
+
+    /// specific rules, when the level doesn't follow
+    /// the ones of its campaign or the default ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ruleset: Option<Ruleset>,
+
+    /// the links from keys to doors and from levers to terrains
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
+
+    /// a text (markdown) shown when the level starts, to tell
+    /// a story or give a hint
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub intro: String,
+
+    /// a text (markdown) shown when the level is won
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub victory: String,
+
+    /// optional goals giving stars
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objectives: Vec<Objective>,
+
+    /// whether the lapin only sees the cells in its line of sight
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fog: bool,
 }
 
 impl Default for Level {
@@ -25,6 +51,12 @@ impl Default for Level {
             terrains: Vec::new(),
             actors: vec![Actor::new(ActorKind::Lapin, 0, 0)],
             items: Vec::new(),
+            ruleset: None,
+            links: Vec::new(),
+            intro: String::new(),
+            victory: String::new(),
+            objectives: Vec::new(),
+            fog: false,
         }
     }
 }
@@ -33,6 +65,10 @@ impl From<&Board> for Level {
     fn from(board: &Board) -> Self {
         let mut level = Level::default();
         level.name = board.name.clone();
+        level.intro = board.intro.clone();
+        level.victory = board.victory.clone();
+        level.objectives = board.objectives.clone();
+        level.fog = board.fog;
         level.default_terrain = board.terrains.default;
         for lc in board.terrains.iter() {
             if lc.v != level.default_terrain {
@@ -42,7 +78,18 @@ impl From<&Board> for Level {
         for lc in board.items.iter_some() {
             level.items.push(lc);
         }
+        // a stable order makes the level signature only depend on its content
+        level.terrains.sort_by_key(|lc| (lc.pos.y, lc.pos.x));
+        level.items.sort_by_key(|lc| (lc.pos.y, lc.pos.x));
         level.actors = board.actors.vec();
+        if !board.ruleset.is_default() {
+            level.ruleset = Some(board.ruleset.clone());
+        }
+        // links whose switch was erased are dropped
+        level.links = board.links.iter()
+            .filter(|link| board.items.get(link.switch).is_some_and(|item| item.kind.is_switch()))
+            .copied()
+            .collect();
         level
     }
 }
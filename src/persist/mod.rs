@@ -3,6 +3,7 @@ mod bag;
 mod format;
 mod level;
 mod read;
+mod text;
 mod write;
 
 pub use {
@@ -13,5 +14,6 @@ pub use {
     },
     level::Level,
     read::*,
+    text::*,
     write::*,
 };
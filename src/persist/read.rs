@@ -1,11 +1,13 @@
-
 use {
     anyhow::Result,
     serde::{
         de::DeserializeOwned,
     },
     std::{
-        fs::File,
+        fs::{
+            self,
+            File,
+        },
         path::Path,
     },
     super::*,
@@ -25,6 +27,9 @@ where
         SerdeFormat::MessagePack => {
             rmp_serde::decode::from_slice(bytes)?
         }
+        SerdeFormat::Text => {
+            return Err(anyhow!("The text format only holds levels"));
+        }
     })
 }
 
@@ -37,10 +42,7 @@ pub fn read_file<T>(
 where
     T: DeserializeOwned,
 {
-    let format = path.extension()
-        .and_then(|os| os.to_str())
-        .and_then(|ext| SerdeFormat::from_key(ext))
-        .unwrap_or_default();
+    let format = SerdeFormat::from_path(path).unwrap_or_default();
     let file = File::open(path)?;
     debug!("read file {:?} with format {:?}", path, format);
     Ok(match format {
@@ -50,5 +52,24 @@ where
         SerdeFormat::MessagePack => {
             rmp_serde::decode::from_read(file)?
         }
+        SerdeFormat::Text => {
+            return Err(anyhow!("The text format only holds levels"));
+        }
     })
 }
+
+/// read a bag from a file, guessing the format from the
+/// file extension. A level file in the text format gives
+/// a bag with just this level.
+pub fn read_bag_file(
+    path: &Path,
+) -> Result<Bag> {
+    match SerdeFormat::from_path(path) {
+        Some(SerdeFormat::Text) => {
+            debug!("read level file {:?} as text", path);
+            let level = read_text(&fs::read_to_string(path)?)?;
+            Ok(Bag::from(level))
+        }
+        _ => read_file(path),
+    }
+}
@@ -0,0 +1,547 @@
+//! A text format for levels, made to be read and written
+//! by humans and to give readable diffs.
+//!
+//! Example:
+//!
+//! ```text
+//! name: "The Pond"
+//! default terrain: stone
+//! origin: -3 -2
+//! legend:
+//!     . mud
+//!     # stone
+//!     " grass
+//!     ~ water
+//!     : sand
+//!     L lapin
+//!     F fox
+//!     c carrot
+//! grid:
+//!     ....~~~.
+//!     ..~~~~~"
+//! overlay:
+//!     L.c.....
+//!     .....F..
+//! ```
+//!
+//! A level with specific rules has them in JSON on a `ruleset` line.
+//! The name is a JSON string, as are the intro and victory texts,
+//! when any, on `intro` and `victory` lines, and the objectives are in JSON
+//! on an `objectives` line. A fog of war level has a `fog: yes` line.
+//! A level with keys or levers lists their targets in a `links`
+//! section, one `switch_x switch_y target_x target_y` per line.
+//!
+//! The grid starts at the origin and covers all the cells
+//! holding a terrain, an actor or an item. The overlay, with
+//! the same origin, draws the actors and items over the terrain
+//! grid, a dot meaning nothing. An item under an actor can't be
+//! drawn, so it's listed, as `kind x y`, in an `items` section.
+//!
+//! Terrains and items are read in lines then columns order, and
+//! actors too, the lapin first. As the order of the actors changes
+//! the game, and the order of all elements changes the signature
+//! of the level, an element stored in another order is given its
+//! rank in the reading order, in a `terrain order`, `item order` or
+//! `actor order` section (for example `0 4-7 1-3`), so that every
+//! level round-trips.
+
+use {
+    anyhow::Result,
+    crate::{
+        core::*,
+        pos::*,
+    },
+    fnv::FnvHashMap,
+    std::fmt::Write,
+    super::Level,
+};
+
+const INDENT: &str = "    ";
+
+/// the char of the cells of the overlay without actor nor item
+const OVERLAY_EMPTY: char = '.';
+
+/// what a char of the legend stands for
+#[derive(Debug, Clone, Copy)]
+enum Glyph {
+    Terrain(Terrain),
+    Actor(ActorKind),
+    Item(ItemKind),
+}
+
+fn glyph_by_name(name: &str) -> Result<Glyph> {
+    terrain_by_name(name).map(Glyph::Terrain)
+        .or_else(|_| actor_kind_by_name(name).map(Glyph::Actor))
+        .or_else(|_| item_kind_by_name(name).map(Glyph::Item))
+        .map_err(|_| anyhow!("unknown terrain, actor or item: {:?}", name))
+}
+
+fn terrain_name(terrain: Terrain) -> String {
+    format!("{:?}", terrain).to_lowercase()
+}
+/// find a terrain by the name of its variant, ignoring case
+pub fn terrain_by_name(name: &str) -> Result<Terrain> {
+    TERRAINS.iter()
+        .find(|&&t| terrain_name(t).eq_ignore_ascii_case(name))
+        .copied()
+        .ok_or_else(|| anyhow!("unknown terrain: {:?}", name))
+}
+fn actor_kind_name(kind: ActorKind) -> String {
+    format!("{:?}", kind).to_lowercase()
+}
+/// find an actor kind by the name of its variant, ignoring case
+pub fn actor_kind_by_name(name: &str) -> Result<ActorKind> {
+    ACTORS.iter()
+        .find(|&&k| actor_kind_name(k).eq_ignore_ascii_case(name))
+        .copied()
+        .ok_or_else(|| anyhow!("unknown actor: {:?}", name))
+}
+fn item_kind_name(kind: ItemKind) -> String {
+    format!("{:?}", kind).to_lowercase()
+}
+fn item_kind_by_name(name: &str) -> Result<ItemKind> {
+    ITEMS.iter()
+        .find(|&&k| item_kind_name(k).eq_ignore_ascii_case(name))
+        .copied()
+        .ok_or_else(|| anyhow!("unknown item: {:?}", name))
+}
+
+/// the area covering all terrains, actors and items
+fn level_area(level: &Level) -> PosArea {
+    PosDistribution::from(
+        level.terrains.iter().map(|lc| lc.pos)
+            .chain(level.actors.iter().map(|a| a.pos))
+            .chain(level.items.iter().map(|lc| lc.pos))
+    )
+    .map_or_else(PosArea::empty, |pd| pd.area)
+}
+
+/// the number of ranges written on a line of an order section
+const RANGES_PER_LINE: usize = 12;
+
+/// the rank of each element in the reading order (given by
+/// the keys), or None when the elements are already in that order
+fn ranks<K: Ord>(keys: &[K]) -> Option<Vec<usize>> {
+    let mut sorted: Vec<usize> = (0..keys.len()).collect();
+    sorted.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+    let mut ranks = vec![0; keys.len()];
+    for (rank, &idx) in sorted.iter().enumerate() {
+        ranks[idx] = rank;
+    }
+    if ranks.iter().enumerate().all(|(idx, &rank)| idx == rank) {
+        None
+    } else {
+        Some(ranks)
+    }
+}
+
+/// write the ranks as ranges like "3-8", on indented lines
+fn write_ranks(s: &mut String, section: &str, ranks: &[usize]) -> Result<()> {
+    let mut ranges: Vec<String> = Vec::new();
+    let mut start = 0;
+    for i in 1..=ranks.len() {
+        if i < ranks.len() && ranks[i] == ranks[i - 1] + 1 {
+            continue;
+        }
+        ranges.push(if i - 1 == start {
+            ranks[start].to_string()
+        } else {
+            format!("{}-{}", ranks[start], ranks[i - 1])
+        });
+        start = i;
+    }
+    writeln!(s, "{}:", section)?;
+    for line in ranges.chunks(RANGES_PER_LINE) {
+        writeln!(s, "{}{}", INDENT, line.join(" "))?;
+    }
+    Ok(())
+}
+
+/// parse the ranges of an indented line of an order section
+fn parse_ranks(line: &str, ranks: &mut Vec<usize>) -> Result<()> {
+    for token in line.split_whitespace() {
+        match token.split_once('-') {
+            Some((start, end)) => ranks.extend(start.parse::<usize>()?..=end.parse()?),
+            None => ranks.push(token.parse()?),
+        }
+    }
+    Ok(())
+}
+
+/// put back in their original order the elements read in reading order
+fn reorder<T: Clone>(elements: &mut Vec<T>, ranks: &[usize]) -> Result<()> {
+    let mut sorted_ranks = ranks.to_vec();
+    sorted_ranks.sort_unstable();
+    if !sorted_ranks.into_iter().eq(0..elements.len()) {
+        return Err(anyhow!("the order doesn't match the {} elements", elements.len()));
+    }
+    *elements = ranks.iter().map(|&rank| elements[rank].clone()).collect();
+    Ok(())
+}
+
+/// the keys giving the reading order of the terrains and items
+fn reading_key(pos: Pos) -> (Int, Int) {
+    (pos.y, pos.x)
+}
+
+/// the keys giving the reading order of the actors, the lapin first
+fn actor_reading_key(actor: &Actor) -> (bool, Int, Int) {
+    (actor.kind != ActorKind::Lapin, actor.pos.y, actor.pos.x)
+}
+
+pub fn level_to_text(level: &Level) -> Result<String> {
+    let mut s = String::new();
+    writeln!(s, "name: {}", serde_json::to_string(&level.name)?)?;
+    writeln!(s, "default terrain: {}", terrain_name(level.default_terrain))?;
+    if !level.intro.is_empty() {
+        writeln!(s, "intro: {}", serde_json::to_string(&level.intro)?)?;
+    }
+    if !level.victory.is_empty() {
+        writeln!(s, "victory: {}", serde_json::to_string(&level.victory)?)?;
+    }
+    if let Some(ruleset) = &level.ruleset {
+        writeln!(s, "ruleset: {}", serde_json::to_string(ruleset)?)?;
+    }
+    if !level.objectives.is_empty() {
+        writeln!(s, "objectives: {}", serde_json::to_string(&level.objectives)?)?;
+    }
+    if level.fog {
+        writeln!(s, "fog: yes")?;
+    }
+    let area = level_area(level);
+    writeln!(s, "origin: {} {}", area.x.start, area.y.start)?;
+    writeln!(s, "legend:")?;
+    for &terrain in TERRAINS {
+        writeln!(s, "{}{} {}", INDENT, terrain_to_char(terrain), terrain_name(terrain))?;
+    }
+    for &kind in ACTORS {
+        writeln!(s, "{}{} {}", INDENT, actor_kind_to_char(kind), actor_kind_name(kind))?;
+    }
+    for &kind in ITEMS {
+        writeln!(s, "{}{} {}", INDENT, item_kind_to_char(kind), item_kind_name(kind))?;
+    }
+    writeln!(s, "grid:")?;
+    let mut terrains = PosMap::new(area.clone(), level.default_terrain);
+    for &lc in &level.terrains {
+        terrains.set_lc(lc);
+    }
+    for y in area.y.clone() {
+        s.push_str(INDENT);
+        for x in area.x.clone() {
+            s.push(terrain_to_char(terrains.get_xy(x, y)));
+        }
+        s.push('\n');
+    }
+    let mut overlay = PosMap::new(area.clone(), OVERLAY_EMPTY);
+    for lc in &level.items {
+        overlay.set(lc.pos, item_kind_to_char(lc.v.kind));
+    }
+    let mut hidden_items: Vec<&Located<Item>> = Vec::new();
+    for actor in &level.actors {
+        if overlay.get(actor.pos) != OVERLAY_EMPTY {
+            hidden_items.extend(level.items.iter().filter(|lc| lc.pos == actor.pos));
+        }
+        overlay.set(actor.pos, actor_kind_to_char(actor.kind));
+    }
+    writeln!(s, "overlay:")?;
+    for y in area.y.clone() {
+        s.push_str(INDENT);
+        for x in area.x.clone() {
+            s.push(overlay.get_xy(x, y));
+        }
+        s.push('\n');
+    }
+    if !hidden_items.is_empty() {
+        writeln!(s, "items:")?;
+        for lc in hidden_items {
+            writeln!(s, "{}{} {} {}", INDENT, item_kind_name(lc.v.kind), lc.pos.x, lc.pos.y)?;
+        }
+    }
+    let terrain_keys: Vec<_> = level.terrains.iter().map(|lc| reading_key(lc.pos)).collect();
+    if let Some(ranks) = ranks(&terrain_keys) {
+        write_ranks(&mut s, "terrain order", &ranks)?;
+    }
+    let item_keys: Vec<_> = level.items.iter().map(|lc| reading_key(lc.pos)).collect();
+    if let Some(ranks) = ranks(&item_keys) {
+        write_ranks(&mut s, "item order", &ranks)?;
+    }
+    let actor_keys: Vec<_> = level.actors.iter().map(actor_reading_key).collect();
+    if let Some(ranks) = ranks(&actor_keys) {
+        write_ranks(&mut s, "actor order", &ranks)?;
+    }
+    if !level.links.is_empty() {
+        writeln!(s, "links:")?;
+        for link in &level.links {
+            writeln!(
+                s, "{}{} {} {} {}", INDENT,
+                link.switch.x, link.switch.y, link.target.x, link.target.y,
+            )?;
+        }
+    }
+    Ok(s)
+}
+
+/// parse "switch_x switch_y target_x target_y"
+fn parse_link(line: &str) -> Result<Link> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    match tokens.as_slice() {
+        [sx, sy, tx, ty] => Ok(Link::new(
+            Pos::new(sx.parse()?, sy.parse()?),
+            Pos::new(tx.parse()?, ty.parse()?),
+        )),
+        _ => Err(anyhow!("expected two positions, got {:?}", line)),
+    }
+}
+
+/// parse "kind x y"
+fn parse_located(line: &str) -> Result<(&str, Pos)> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    match tokens.as_slice() {
+        [name, x, y] => Ok((name, Pos::new(x.parse()?, y.parse()?))),
+        _ => Err(anyhow!("expected a name and a position, got {:?}", line)),
+    }
+}
+
+pub fn level_from_text(text: &str) -> Result<Level> {
+    let mut level = Level {
+        actors: Vec::new(),
+        ..Level::default()
+    };
+    let mut origin = Pos::new(0, 0);
+    let mut legend: FnvHashMap<char, Glyph> = TERRAINS.iter()
+        .map(|&t| (terrain_to_char(t), Glyph::Terrain(t)))
+        .chain(ACTORS.iter().map(|&k| (actor_kind_to_char(k), Glyph::Actor(k))))
+        .chain(ITEMS.iter().map(|&k| (item_kind_to_char(k), Glyph::Item(k))))
+        .collect();
+    let mut grid: Vec<&str> = Vec::new();
+    let mut overlay: Vec<&str> = Vec::new();
+    let mut terrain_ranks: Vec<usize> = Vec::new();
+    let mut item_ranks: Vec<usize> = Vec::new();
+    let mut actor_ranks: Vec<usize> = Vec::new();
+    let mut section = "";
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            let content = line.trim();
+            match section {
+                "legend" => {
+                    let mut chars = content.chars();
+                    let (c, name) = (chars.next(), chars.as_str().trim());
+                    legend.insert(c.unwrap(), glyph_by_name(name)?);
+                }
+                "grid" => {
+                    grid.push(content);
+                }
+                "overlay" => {
+                    overlay.push(content);
+                }
+                "items" => {
+                    let (name, pos) = parse_located(content)?;
+                    let kind = item_kind_by_name(name)?;
+                    level.items.push(Located::new(pos, Item { kind }));
+                }
+                "links" => {
+                    level.links.push(parse_link(content)?);
+                }
+                "terrain order" => {
+                    parse_ranks(content, &mut terrain_ranks)?;
+                }
+                "item order" => {
+                    parse_ranks(content, &mut item_ranks)?;
+                }
+                "actor order" => {
+                    parse_ranks(content, &mut actor_ranks)?;
+                }
+                _ => {
+                    return Err(anyhow!("unexpected indented line: {:?}", line));
+                }
+            }
+            continue;
+        }
+        let (key, value) = match line.find(':') {
+            Some(idx) => (&line[..idx], line[idx+1..].trim()),
+            None => return Err(anyhow!("unexpected line: {:?}", line)),
+        };
+        match key {
+            "name" => {
+                level.name = serde_json::from_str(value)?;
+            }
+            "default terrain" => {
+                level.default_terrain = terrain_by_name(value)?;
+            }
+            "ruleset" => {
+                level.ruleset = Some(serde_json::from_str(value)?);
+            }
+            "objectives" => {
+                level.objectives = serde_json::from_str(value)?;
+            }
+            "fog" => {
+                level.fog = match value {
+                    "yes" => true,
+                    "no" => false,
+                    _ => return Err(anyhow!("unexpected fog value: {:?}", value)),
+                };
+            }
+            "intro" => {
+                level.intro = serde_json::from_str(value)?;
+            }
+            "victory" => {
+                level.victory = serde_json::from_str(value)?;
+            }
+            "origin" => {
+                let (_, pos) = parse_located(&format!("origin {}", value))?;
+                origin = pos;
+            }
+            "legend" | "grid" | "overlay" | "items" | "links"
+            | "terrain order" | "item order" | "actor order" => {
+                section = key;
+            }
+            _ => {
+                return Err(anyhow!("unexpected key: {:?}", key));
+            }
+        }
+    }
+    for (dy, line) in grid.iter().enumerate() {
+        for (dx, c) in line.chars().enumerate() {
+            let terrain = match legend.get(&c) {
+                Some(&Glyph::Terrain(terrain)) => terrain,
+                _ => return Err(anyhow!("char {:?} isn't a terrain of the legend", c)),
+            };
+            if terrain != level.default_terrain {
+                let pos = Pos::new(origin.x + dx as Int, origin.y + dy as Int);
+                level.terrains.push(Located::new(pos, terrain));
+            }
+        }
+    }
+    for (dy, line) in overlay.iter().enumerate() {
+        for (dx, c) in line.chars().enumerate() {
+            let pos = Pos::new(origin.x + dx as Int, origin.y + dy as Int);
+            match legend.get(&c) {
+                _ if c == OVERLAY_EMPTY => {}
+                Some(&Glyph::Actor(kind)) => {
+                    level.actors.push(Actor::new(kind, pos.x, pos.y));
+                }
+                Some(&Glyph::Item(kind)) => {
+                    level.items.push(Located::new(pos, Item { kind }));
+                }
+                _ => return Err(anyhow!("char {:?} isn't an actor or item of the legend", c)),
+            }
+        }
+    }
+    level.items.sort_by_key(|lc| reading_key(lc.pos));
+    level.actors.sort_by_key(actor_reading_key);
+    if !terrain_ranks.is_empty() {
+        reorder(&mut level.terrains, &terrain_ranks)?;
+    }
+    if !item_ranks.is_empty() {
+        reorder(&mut level.items, &item_ranks)?;
+    }
+    if !actor_ranks.is_empty() {
+        reorder(&mut level.actors, &actor_ranks)?;
+    }
+    match level.actors.first() {
+        Some(actor) if actor.kind == ActorKind::Lapin => Ok(level),
+        _ => Err(anyhow!("the first actor must be the lapin")),
+    }
+}
+
+/// write a level as text
+pub fn write_text<W>(
+    w: &mut W,
+    level: &Level,
+) -> Result<()>
+where
+    W: std::io::Write + ?Sized,
+{
+    write!(w, "{}", level_to_text(level)?)?;
+    Ok(())
+}
+
+/// read a level written as text
+pub fn read_text(text: &str) -> Result<Level> {
+    level_from_text(text)
+}
+
+#[cfg(test)]
+mod text_tests {
+
+    use {
+        super::*,
+        crate::win_db::Signature,
+    };
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut board = Board::from_ascii(r#"
+            ..~~:"
+            .L#Fc#
+            w..K..
+        "#).unwrap();
+        board.name = "A: test".to_string();
+        board.intro = "Eat the *carrot*,\nnot the \"fox\"".to_string();
+        board.objectives = vec![Objective::MaxMoves(8), Objective::AllCarrots];
+        board.fog = true;
+        board.add_item_in(ItemKind::Key, 0, 1);
+        board.add_item_in(ItemKind::Door, 2, 2);
+        board.add_item_in(ItemKind::Wine, 3, 1); // under the fox
+        board.links.push(Link::new(Pos::new(0, 1), Pos::new(2, 2)));
+        let level = Level::from(&board);
+        let text = level_to_text(&level).unwrap();
+        assert!(text.contains("overlay:\n    ......\n    kL.Fc.\n    w.dK..\n"));
+        assert!(text.contains("items:\n    wine 3 1\n"));
+        let read_level = level_from_text(&text).unwrap();
+        assert_eq!(level_to_text(&read_level).unwrap(), text);
+        assert_eq!(
+            Signature::new(&read_level).unwrap(),
+            Signature::new(&level).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_text_order_round_trip() {
+        let mut board = Board::from_ascii(r#"
+            F.L.~
+            ..W.:
+        "#).unwrap();
+        board.add_item_in(ItemKind::Carrot, 3, 1);
+        board.add_item_in(ItemKind::Carrot, 1, 0);
+        let mut level = Level::from(&board);
+        level.actors = vec![level.actors[0], level.actors[2], level.actors[1]];
+        level.terrains.reverse();
+        level.items.reverse();
+        let text = level_to_text(&level).unwrap();
+        assert!(text.contains("item order:\n    1 0\n"));
+        assert!(text.contains("actor order:\n    0 2 1\n"));
+        let read_level = level_from_text(&text).unwrap();
+        assert_eq!(
+            Signature::new(&read_level).unwrap(),
+            Signature::new(&level).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_included_levels_round_trip() {
+        let loaded_campaign = crate::included::loaded_campaign().unwrap();
+        for ll in &loaded_campaign.levels {
+            let text = level_to_text(&ll.level).unwrap();
+            let read_level = level_from_text(&text).unwrap();
+            assert_eq!(
+                Signature::new(&read_level).unwrap(),
+                Signature::new(&ll.level).unwrap(),
+                "level {:?} doesn't round-trip",
+                ll.level.name,
+            );
+        }
+    }
+
+    #[test]
+    fn test_text_name_round_trip() {
+        let mut board = Board::from_ascii(".L.").unwrap();
+        board.name = "  two\nlines: \"quoted\"  ".to_string();
+        let level = Level::from(&board);
+        let read_level = level_from_text(&level_to_text(&level).unwrap()).unwrap();
+        assert_eq!(read_level.name, level.name);
+    }
+}
@@ -5,7 +5,10 @@ use {
     std::{
         fs::File,
         io::Write,
-        path::Path,
+        path::{
+            Path,
+            PathBuf,
+        },
     },
     super::*,
 };
@@ -23,17 +26,42 @@ pub fn write_file<T>(
 where
     T: Serialize,
 {
-    let format = requested_format
-        .or(suggested_path.extension()
-            .and_then(|os| os.to_str())
-            .and_then(|ext| SerdeFormat::from_key(ext))
-        )
-        .unwrap_or_default();
-    let path = suggested_path.with_extension(format.key());
+    let (path, format) = file_path_and_format(suggested_path, requested_format);
     let mut file = File::create(path)?;
     write(&mut file, val, format, pretty)
 }
 
+/// write a bag to a file, like `write_file`, but accepting the
+/// text format when the bag only holds one level
+pub fn write_bag_file(
+    bag: &Bag,
+    suggested_path: &Path,
+    requested_format: Option<SerdeFormat>,
+    pretty: bool,
+) -> Result<()> {
+    let (path, format) = file_path_and_format(suggested_path, requested_format);
+    if format != SerdeFormat::Text {
+        return write_file(bag, &path, Some(format), pretty);
+    }
+    let level = match bag.levels.values().next() {
+        Some(level) if bag.campaigns.is_empty() && bag.levels.len() == 1 => level,
+        _ => return Err(anyhow!("Only single levels can be written as text")),
+    };
+    let mut file = File::create(path)?;
+    write_text(&mut file, level)
+}
+
+/// the real path and format of a file to write
+fn file_path_and_format(
+    suggested_path: &Path,
+    requested_format: Option<SerdeFormat>,
+) -> (PathBuf, SerdeFormat) {
+    let format = requested_format
+        .or_else(|| SerdeFormat::from_path(suggested_path))
+        .unwrap_or_default();
+    (format.path(suggested_path), format)
+}
+
 /// write an object (typically a Level) to a stream
 pub fn write<W: ?Sized, T>(
     w: &mut W,
@@ -55,9 +83,69 @@ where
             write!(w, "{}", serialized)?;
         }
         SerdeFormat::MessagePack => {
-            rmp_serde::encode::write(w, val)?;
+            // structs are written as maps, as optional fields
+            // skipped in the middle would break a positional array
+            rmp_serde::encode::write_named(w, val)?;
+        }
+        SerdeFormat::Text => {
+            return Err(anyhow!("The text format only holds levels"));
         }
     }
     Ok(())
 }
 
+
+#[cfg(test)]
+mod write_tests {
+
+    use {
+        super::*,
+        crate::{
+            campaign::Campaign,
+            core::*,
+            pos::*,
+        },
+    };
+
+    fn mpack_round_trip<T>(val: &T) -> T
+    where
+        T: Serialize + serde::de::DeserializeOwned,
+    {
+        let mut bytes = Vec::new();
+        write(&mut bytes, val, SerdeFormat::MessagePack, false).unwrap();
+        read_bytes(&bytes, SerdeFormat::MessagePack).unwrap()
+    }
+
+    #[test]
+    fn test_level_mpack_round_trip() {
+        // optional fields are skipped before the last ones
+        let mut board = Board::from_ascii(r#"
+            L.k.d"
+        "#).unwrap();
+        board.intro = "Open the door".to_string();
+        board.objectives = vec![Objective::AllCarrots];
+        board.fog = true;
+        board.links.push(Link::new(Pos::new(2, 0), Pos::new(4, 0)));
+        let level = Level::from(&board);
+        assert!(level.ruleset.is_none());
+        let read = mpack_round_trip(&level);
+        assert_eq!(read.links, level.links);
+        assert_eq!(read.intro, level.intro);
+        assert_eq!(read.objectives, level.objectives);
+        assert!(read.fog);
+        assert!(read.ruleset.is_none());
+    }
+
+    #[test]
+    fn test_campaign_mpack_round_trip() {
+        let campaign = Campaign {
+            name: "stars".to_string(),
+            levels: vec!["a".to_string(), "b".to_string()],
+            stars_per_level: Some(2),
+            ..Campaign::default()
+        };
+        let read = mpack_round_trip(&campaign);
+        assert_eq!(read.levels, campaign.levels);
+        assert_eq!(read.stars_per_level, Some(2));
+    }
+}
@@ -4,10 +4,14 @@ use {
     },
 };
 
+mod saved_ends;
 mod state;
+mod turn;
 
 pub use {
+    saved_ends::SavedEnds,
     state::PlayLevelState,
+    turn::Turn,
 };
 
 pub const LAYOUT: Layout = Layout {
@@ -0,0 +1,58 @@
+use {
+    crate::pos::Dir,
+};
+
+/// what was saved of the ends of a game, as the player may
+/// undo an end and play again: the replay is saved at the first
+/// end and for each new win, and every new win is saved, whatever
+/// the ends before it.
+#[derive(Debug, Default)]
+pub struct SavedEnds {
+    replay: bool, // whether a replay of the game was saved
+    wins: Vec<Vec<Dir>>, // the moves of the wins already saved
+}
+
+impl SavedEnds {
+
+    /// tell whether the win made of these moves must be saved,
+    /// noting it's saved
+    pub fn save_win(&mut self, moves: Vec<Dir>) -> bool {
+        if self.wins.contains(&moves) {
+            false
+        } else {
+            self.wins.push(moves);
+            true
+        }
+    }
+
+    /// tell whether a replay must be saved for this end of the game,
+    /// noting it's saved
+    pub fn save_replay(&mut self, new_win: bool) -> bool {
+        let first_end = !self.replay;
+        self.replay = true;
+        first_end || new_win
+    }
+}
+
+#[cfg(test)]
+mod saved_ends_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_lose_undo_win() {
+        use Dir::*;
+        let mut saved = SavedEnds::default();
+        // the lapin loses
+        assert!(saved.save_replay(false));
+        // the player undoes the last move and wins
+        assert!(saved.save_win(vec![Up, Right]));
+        assert!(saved.save_replay(true));
+        // then undoes the win and plays it again
+        assert!(!saved.save_win(vec![Up, Right]));
+        assert!(!saved.save_replay(false));
+        // or wins another way, which may be better
+        assert!(saved.save_win(vec![Up, Up]));
+        assert!(saved.save_replay(true));
+    }
+}
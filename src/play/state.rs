@@ -8,6 +8,7 @@ use {
         },
         core::*,
         display::{
+            draw_text_box,
             BoardDrawer,
             Screen,
             Status,
@@ -15,9 +16,12 @@ use {
         edit,
         persist::Level,
         pos::*,
+        replay::Replay,
         win_db::{
             self,
+            Score,
             Signature,
+            WinFile,
         },
     },
     crossterm::{
@@ -29,63 +33,165 @@ use {
     },
     std::{
         io::Write,
-        time::SystemTime,
+        time::Instant,
+    },
+    super::{
+        LAYOUT,
+        SavedEnds,
+        Turn,
     },
-    super::LAYOUT,
     termimad::{
         Event,
     },
 };
 
 pub struct PlayLevelState {
+    level: Level,
+    campaign_ruleset: Option<Ruleset>, // applies when the level has no ruleset
     level_signature: Signature,
+    previous_state: Option<&'static str>,
     comes_from_editor: bool,
+    recorded: bool, // whether the win and the replay are saved
+    saved_ends: SavedEnds, // what was saved of the ends of this game
     board: Board,
     center: Pos,    // the pos shown at center of the screen
     status: Status,
+    rng: WorldRng,  // gives the seed of the next world move
+    history: Vec<Turn>,
+    turns: usize,   // all turns played, including the undone ones
+    start: Instant, // when the level was opened
+    message: Option<String>, // markdown shown over the board until a key is hit
+    vision: Option<Vision>, // what the lapin sees and remembers, in a fog of war level
+    threats_shown: bool, // whether the planned moves of the enemies are drawn
+    hint: Option<Dir>, // the suggested move, shown until the lapin moves
+    hints: usize, // number of hints given in the game
+}
+
+/// the message shown over the board, if the text isn't empty
+fn message(text: &str) -> Option<String> {
+    if text.trim().is_empty() {
+        None
+    } else {
+        Some(format!("{}\n\n*hit any key to continue*", text))
+    }
 }
 
 impl PlayLevelState {
 
-    /// create a new game state. When coming from editor
-    /// the win is not saved
+    /// create a new game state. When not `recorded` (e.g. when
+    /// coming from editor) the win and the replay aren't saved.
+    ///
+    /// When no seed is given, it's taken from the time
     pub fn new(
         level: &Level,
+        campaign_ruleset: Option<Ruleset>,
         previous_state: Option<&'static str>,
+        seed: Option<usize>,
+        recorded: bool,
     ) -> Result<Self> {
-        let board = Board::from(level);
-        let status = Status::from_message(
-            if let Some(state) = previous_state {
-                format!(
-                    "Hit *arrows* to move, *?* for help, *esc* to go back to {}, *q* to quit",
-                    state,
-                )
-            } else {
-                "Hit *arrows* to move, *?* for help, *q* to quit".to_string()
-            }
-        );
+        let mut board = Board::from(level);
+        board.apply_campaign_ruleset(level, campaign_ruleset.as_ref());
+        let status = Self::initial_status(previous_state);
         let level_signature = Signature::new(level)?;
         let comes_from_editor = previous_state == Some(edit::LABEL);
         let center = board.lapin_pos();
+        let rng = seed.map_or_else(WorldRng::from_time, WorldRng::new);
+        let vision = board.fog.then(|| Vision::new(&board));
         Ok(Self {
+            level: level.clone(),
+            campaign_ruleset,
             level_signature,
+            previous_state,
             comes_from_editor,
+            recorded,
+            saved_ends: SavedEnds::default(),
             board,
             center,
             status,
+            rng,
+            history: Vec::new(),
+            turns: 0,
+            start: Instant::now(),
+            message: message(&level.intro),
+            vision,
+            threats_shown: false,
+            hint: None,
+            hints: 0,
         })
     }
 
+    fn initial_status(previous_state: Option<&'static str>) -> Status {
+        Status::from_message(
+            if let Some(state) = previous_state {
+                format!(
+                    "Hit *arrows* to move, *?* for help, *esc* to go back to {}, *q* to quit",
+                    state,
+                )
+            } else {
+                "Hit *arrows* to move, *?* for help, *q* to quit".to_string()
+            }
+        )
+    }
+
     fn handle_player_dir(
         &mut self,
         dir: Dir,
     ) -> Option<StateTransition> {
+        let before = self.board.state();
+        self.hint = None;
         let move_result = self.board.apply_player_move(dir);
+        if !matches!(move_result, MoveResult::Invalid) {
+            self.turns += 1;
+            self.history.push(Turn {
+                before,
+                dir,
+                seed: self.rng.seed(),
+                world_replied: false,
+            });
+        }
         self.apply(move_result);
         self.center = self.board.lapin_pos();
         None
     }
 
+    /// rewind to the state before the last lapin move
+    /// and the world's reply.
+    ///
+    /// `turns` isn't decremented: it counts the undone moves too.
+    fn undo(&mut self) -> Option<StateTransition> {
+        self.hint = None;
+        if let Some(turn) = self.history.pop() {
+            self.board.restore(&turn.before);
+            self.rng = WorldRng::new(turn.seed);
+            self.center = self.board.lapin_pos();
+            self.status = Self::initial_status(self.previous_state);
+        } else {
+            self.status = Status::from_error("Nothing to undo".to_string());
+        }
+        None
+    }
+
+    /// go back to the start of the level, for a new game
+    fn restart(&mut self) -> Option<StateTransition> {
+        if let Some(turn) = self.history.first() {
+            self.rng = WorldRng::new(turn.seed);
+        }
+        self.history.clear();
+        self.turns = 0;
+        self.start = Instant::now();
+        self.hint = None;
+        self.hints = 0;
+        self.saved_ends = SavedEnds::default();
+        self.board.reset_to(&self.level);
+        self.board.apply_campaign_ruleset(&self.level, self.campaign_ruleset.as_ref());
+        if self.board.fog {
+            self.vision = Some(Vision::new(&self.board));
+        }
+        self.center = self.board.lapin_pos();
+        self.status = Self::initial_status(self.previous_state);
+        None
+    }
+
     fn handle_screen_dir(
         &mut self,
         dir: Dir,
@@ -94,6 +200,45 @@ impl PlayLevelState {
         None
     }
 
+    fn toggle_threats(&mut self) -> Option<StateTransition> {
+        self.threats_shown = !self.threats_shown;
+        self.status = if self.threats_shown {
+            Status::from_message(
+                "Arrows show the next moves of the animals, red cells would kill you - hit *t* to hide".to_string()
+            )
+        } else {
+            Self::initial_status(self.previous_state)
+        };
+        None
+    }
+
+    /// look for a safe move toward the grass and show it.
+    /// In fog of war, the enemies the lapin doesn't see are
+    /// ignored, so that the hint doesn't reveal them
+    fn give_hint(&mut self) -> Option<StateTransition> {
+        if self.board.current_player != Player::Lapin {
+            return None;
+        }
+        let mut board = self.board.clone();
+        if let Some(vision) = &mut self.vision {
+            vision.update(&self.board);
+            vision.hide_unseen_actors(&mut board);
+        }
+        self.hint = time!(Info, "hint", hint(&board, self.rng));
+        self.status = match self.hint {
+            Some(dir) => {
+                self.hints += 1;
+                Status::from_message(format!(
+                    "Try going **{}** - hint {} of this game",
+                    format!("{:?}", dir).to_lowercase(),
+                    self.hints,
+                ))
+            }
+            None => Status::from_error("No safe move found, try undoing".to_string()),
+        };
+        None
+    }
+
     fn center_on_lapin(&mut self) -> Option<StateTransition> {
         self.center = self.board.lapin_pos();
         None
@@ -117,6 +262,14 @@ impl PlayLevelState {
             (_,    _,     Char('q')) => Some(StateTransition::Quit),
             (_,    _,     Char('Q')) => Some(StateTransition::Quit),
             (_,    false, Char('?')) => Some(StateTransition::Help),
+            (_,    false, Char('u')) => self.undo(),
+            (_,    false, Char('U')) => self.undo(),
+            (_,    false, Char('r')) => self.restart(),
+            (_,    false, Char('R')) => self.restart(),
+            (_,    false, Char('h')) => self.give_hint(),
+            (_,    false, Char('H')) => self.give_hint(),
+            (_,    false, Char('t')) => self.toggle_threats(),
+            (_,    false, Char('T')) => self.toggle_threats(),
             (true, false, Char('c')) => self.center_on_lapin(),
             (true, false, Char('C')) => self.center_on_lapin(),
             (true, true,  Up)        => self.handle_screen_dir(Dir::Up),
@@ -127,32 +280,101 @@ impl PlayLevelState {
         }
     }
 
-    fn set_end_status(&mut self, reason: &str, win: bool) {
+    fn set_end_status(&mut self, reason: &str, win: bool, details: &str) {
         self.status = Status::from(
             format!(
-                "{} You **{}!** - hit *q* to quit, *esc* to go back to {}",
+                "{} You **{}!**{} - hit *u* to undo, *r* to restart, *q* to quit, *esc* to go back to {}",
                 reason,
                 if win { "WIN" } else { "LOSE" },
+                details,
                 if self.comes_from_editor { "editor" } else { "home" },
             ),
             !win
         );
     }
 
+    /// how the player did, up to now
+    fn score(&self) -> Score {
+        let initial_carrots = self.level.items.iter()
+            .filter(|lc| lc.v.kind == ItemKind::Carrot)
+            .count();
+        let remaining_carrots = self.board.items.iter_some()
+            .filter(|lc| lc.v.kind == ItemKind::Carrot)
+            .count();
+        let moves = self.history.len();
+        Score {
+            moves,
+            turns: self.turns,
+            carrots: initial_carrots.saturating_sub(remaining_carrots),
+            millis: self.start.elapsed().as_millis() as u64,
+            stars: stars(&self.level.objectives, &self.board, moves),
+            hints: self.hints,
+        }
+    }
+
+    /// the score of the win, and the previous bests, as shown
+    /// in the status. The win is saved when `save` is true.
+    fn record_win(&self, save: bool) -> String {
+        let score = self.score();
+        let mut details = format!(
+            " in **{}** moves ({}) {}",
+            score.moves,
+            score.time(),
+            star_marks(score.stars),
+        );
+        if score.hints > 0 {
+            details.push_str(&format!(" with {} hint(s)", score.hints));
+        }
+        if !save {
+            return details;
+        }
+        if let Ok(win_file) = WinFile::load() {
+            if let Some(best) = win_file.best(&self.level_signature) {
+                details.push_str(&format!(" - your best: {} moves", best.moves));
+            }
+            if let Some(par) = win_file.par(&self.level_signature) {
+                details.push_str(&format!(" - par: {} moves", par.moves));
+            }
+        }
+        if let Err(e) = win_db::save_win(&self.level_signature, score) {
+            warn!("Saving win failed: {:?}", e);
+        }
+        details
+    }
+
+    /// record the game as a replay
+    fn save_replay(&self) {
+        let replay = Replay::new(
+            self.level_signature.clone(),
+            self.level.clone(),
+            self.campaign_ruleset.clone(),
+            &self.history,
+        );
+        match replay.save() {
+            Ok(path) => info!("replay saved in {:?}", path),
+            Err(e) => warn!("Saving replay failed: {:?}", e),
+        }
+    }
+
     /// change the state accordingly to the move_result
     /// returned on a move by either the player or the world
     fn apply(&mut self, move_result: MoveResult) {
         match move_result {
             MoveResult::PlayerWin(s) => {
-                self.set_end_status(&s, true);
-                if !self.comes_from_editor {
-                    if let Err(e) = win_db::save_win(&self.level_signature) {
-                        warn!("Saving win failed: {:?}", e);
-                    }
+                let moves = self.history.iter().map(|turn| turn.dir).collect();
+                let save = self.recorded && self.saved_ends.save_win(moves);
+                if self.recorded && self.saved_ends.save_replay(save) {
+                    self.save_replay();
                 }
+                let details = self.record_win(save);
+                self.set_end_status(&s, true, &details);
+                self.message = message(&self.level.victory);
             }
             MoveResult::PlayerLose(s) => {
-                self.set_end_status(&s, false);
+                if self.recorded && self.saved_ends.save_replay(false) {
+                    self.save_replay();
+                }
+                self.set_end_status(&s, false, "");
             }
             _ => {}
         }
@@ -165,6 +387,20 @@ impl PlayLevelState {
     ) -> Result<()> {
         self.status.display(con, screen)
     }
+
+    /// what the enemies plan, when they're shown and it's the lapin's turn
+    fn threats(&self) -> Option<Threats> {
+        (self.threats_shown && self.board.current_player == Player::Lapin)
+            .then(|| Threats::compute(&self.board, self.rng.seed()))
+    }
+
+    fn board_drawer<'d>(&'d mut self, screen: &'d Screen) -> BoardDrawer<'d> {
+        if let Some(vision) = &mut self.vision {
+            vision.update(&self.board);
+        }
+        BoardDrawer::new(&self.board, screen, self.center)
+            .with_vision(self.vision.as_ref())
+    }
 }
 
 impl State for PlayLevelState {
@@ -178,22 +414,37 @@ impl State for PlayLevelState {
         con: &mut Context,
     ) -> Result<StateTransition> {
         let mut screen = Screen::new(LAYOUT);
-        let mut seed = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map_or(0, |d| (d.as_secs()%7) as usize);
         loop {
-            BoardDrawer::new(&self.board, &screen, self.center).draw(con)?;
+            let threats = self.threats();
+            let hint = self.hint;
+            let mut bd = self.board_drawer(&screen);
+            bd.draw(con)?;
+            if let Some(threats) = &threats {
+                bd.draw_threats(con, threats)?;
+            }
+            if let Some(dir) = hint {
+                bd.draw_hint(con, dir)?;
+            }
+            if let Some(message) = &self.message {
+                draw_text_box(con, &screen, message)?;
+            }
             self.write_status(con, &screen)?;
             con.w.flush()?;
-            if self.board.current_player == Player::World {
-                let world_player = WorldPlayer::new(&self.board, seed);
-                seed += 1;
-                let mut world_move = time!(Info, "world play", world_player.play());
+            if self.board.current_player == Player::World && self.message.is_none() {
+                if let Some(turn) = self.history.last_mut() {
+                    turn.world_replied = true;
+                }
                 let actors = self.board.actors.clone();
-                let move_result = self.board.apply_world_move(&mut world_move);
-                let mut bd = BoardDrawer::new(&self.board, &screen, self.center);
-                bd.animate(con, &actors, &world_move)?;
-                bd.draw(con)?;
+                let (world_move, move_result) = time!(
+                    Info,
+                    "world play",
+                    self.board.play_world(&mut self.rng),
+                );
+                // the moves are shown as seen before them
+                BoardDrawer::new(&self.board, &screen, self.center)
+                    .with_vision(self.vision.as_ref())
+                    .animate(con, &actors, &world_move)?;
+                self.board_drawer(&screen).draw(con)?;
                 con.w.flush()?;
                 self.apply(move_result);
             } else {
@@ -201,6 +452,9 @@ impl State for PlayLevelState {
                 let event = con.dam.next_event().unwrap();
                 con.dam.unblock();
                 match event {
+                    Event::Key(_) | Event::Click(..) if self.message.is_some() => {
+                        self.message = None;
+                    }
                     Event::Key(ke) => {
                         let next_state = self.handle_key_event(ke);
                         if let Some(next_state) = next_state {
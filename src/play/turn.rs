@@ -0,0 +1,21 @@
+use {
+    crate::{
+        core::*,
+        pos::*,
+    },
+};
+
+/// a lapin move and the world's reply, with what's
+/// needed to rewind it or to play it again
+#[derive(Debug, Clone)]
+pub struct Turn {
+    /// the board state before the lapin's move
+    pub before: BoardState,
+    /// the move of the lapin
+    pub dir: Dir,
+    /// the seed given to the world for its reply
+    pub seed: usize,
+    /// whether the world replied (it doesn't when
+    /// the lapin eats a carrot or the game ends)
+    pub world_replied: bool,
+}
@@ -0,0 +1,207 @@
+use {
+    anyhow::Result,
+    crate::{
+        app::{
+            Context,
+            State,
+            StateTransition,
+        },
+        display::{
+            mad_skin,
+            Screen,
+            Status,
+        },
+        persist::Level,
+        win_db::{
+            self,
+            WinFile,
+        },
+    },
+    crossterm::{
+        event::{
+            KeyCode,
+            KeyEvent,
+        },
+    },
+    std::{
+        io::Write,
+    },
+    super::*,
+    termimad::{
+        Area,
+        Event,
+        InputField,
+        TextView,
+    },
+};
+
+const DEFAULT_STATUS: &str = "Hit *↓* and *↑* to change the selection, *enter* to choose, *q* to quit";
+const NEW_PLAYER_PROMPT: &str = "Name of the new player:";
+
+/// A screen, shown at startup as soon as there's a known player,
+/// letting the user tell who's playing or add a new player
+pub struct ChooseProfileState {
+    names: Vec<String>,
+    selection: usize, // index of the selected name, names.len() for a new player
+    status: Status,
+    input: Option<InputField>, // the name of a new player, when typed
+}
+
+impl ChooseProfileState {
+    pub fn new(win_file: &WinFile) -> Self {
+        let names: Vec<String> = win_file.profile_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let selection = names.iter()
+            .position(|name| name == win_file.current_profile())
+            .unwrap_or(0);
+        Self {
+            names,
+            selection,
+            status: Status::from_message(DEFAULT_STATUS.to_string()),
+            input: None,
+        }
+    }
+
+    fn markdown(&self) -> Result<String> {
+        use std::fmt::Write;
+        let mut md = String::new();
+        write!(md, "\n# Who's playing?\n")?;
+        for (i, name) in self.names.iter().enumerate() {
+            write!(
+                md,
+                "\n{}{}",
+                if i == self.selection { "### " } else { "" },
+                name,
+            )?;
+        }
+        write!(
+            md,
+            "\n\n{}*a new player*",
+            if self.selection == self.names.len() { "### " } else { "" },
+        )?;
+        Ok(md)
+    }
+
+    /// make the player the current one and go to the next screen
+    fn choose(&mut self, name: &str) -> Option<StateTransition> {
+        match win_db::select_profile(name) {
+            Ok(()) => Some(StateTransition::Back),
+            Err(e) => {
+                warn!("profile selection failed: {:?}", e);
+                self.status = Status::from_error("The player couldn't be saved".to_string());
+                None
+            }
+        }
+    }
+
+    fn handle_key_event(
+        &mut self,
+        code: KeyCode,
+    ) -> Option<StateTransition> {
+        if let Some(input) = &mut self.input {
+            if input.apply_keycode_event(code) {
+                return None;
+            }
+            return match code {
+                KeyCode::Esc => {
+                    self.input = None;
+                    None
+                }
+                KeyCode::Enter => {
+                    let name = input.get_content().trim().to_string();
+                    if name.is_empty() {
+                        None
+                    } else {
+                        self.input = None;
+                        self.choose(&name)
+                    }
+                }
+                _ => None,
+            };
+        }
+        match code {
+            KeyCode::Up if self.selection > 0 => {
+                self.selection -= 1;
+                None
+            }
+            KeyCode::Down if self.selection < self.names.len() => {
+                self.selection += 1;
+                None
+            }
+            KeyCode::Enter if self.selection == self.names.len() => {
+                self.input = Some(InputField::new(Area::uninitialized()));
+                None
+            }
+            KeyCode::Enter => {
+                let name = self.names[self.selection].clone();
+                self.choose(&name)
+            }
+            KeyCode::Char('?') => Some(StateTransition::Help),
+            KeyCode::Char('q') | KeyCode::Char('Q') => Some(StateTransition::Quit),
+            _ => None,
+        }
+    }
+
+    fn write_status(
+        &mut self,
+        con: &mut Context,
+        screen: &Screen,
+    ) -> Result<()> {
+        if let Some(input) = &mut self.input {
+            Status::from_message(NEW_PLAYER_PROMPT.to_string()).display(con, screen)?;
+            let area = &screen.areas.status;
+            let left = area.left + NEW_PLAYER_PROMPT.chars().count() as u16 + 2;
+            input.change_area(left, area.top, area.width.saturating_sub(left + 1));
+            input.display_on(con.w)?;
+            Ok(())
+        } else {
+            self.status.display(con, screen)
+        }
+    }
+}
+
+impl State for ChooseProfileState {
+
+    fn label(&self) -> &'static str {
+        "player choice"
+    }
+
+    fn run(
+        &mut self,
+        con: &mut Context,
+    ) -> Result<StateTransition> {
+        let mut screen = Screen::new(LAYOUT);
+        let skin = mad_skin::make(&con.skin);
+        loop {
+            self.write_status(con, &screen)?;
+            let md = self.markdown()?;
+            let text = skin.area_text(&md, &screen.areas.board);
+            TextView::from(&screen.areas.board, &text).write_on(con.w)?;
+            con.w.flush()?;
+            let event = con.dam.next_event().unwrap();
+            con.dam.unblock();
+            match event {
+                Event::Key(KeyEvent { code, .. }) => {
+                    if let Some(next_state) = self.handle_key_event(code) {
+                        return Ok(next_state);
+                    }
+                }
+                Event::Resize(width, height) => {
+                    screen.set_terminal_size(width, height);
+                }
+                _ => {
+                    debug!("ignored event: {:?}", event);
+                }
+            }
+        }
+    }
+
+    fn get_level(
+        &self,
+        _level_idx: usize,
+    ) -> Option<Level> {
+        None
+    }
+}
@@ -0,0 +1,21 @@
+//! recording of games, and their playback
+
+use {
+    crate::{
+        display::Layout,
+    },
+};
+
+mod record;
+mod state;
+
+pub use {
+    record::Replay,
+    state::ReplayState,
+};
+
+pub const LAYOUT: Layout = Layout {
+    header_height: 0,
+    pen_panel_height: 0,
+    status_height: 1,
+};
@@ -0,0 +1,101 @@
+use {
+    anyhow::Result,
+    crate::{
+        core::Ruleset,
+        persist::{
+            self,
+            Level,
+            SerdeFormat,
+        },
+        play::Turn,
+        pos::*,
+        win_db::Signature,
+    },
+    dirs,
+    serde::{Serialize, Deserialize},
+    std::{
+        fs,
+        path::{
+            Path,
+            PathBuf,
+        },
+        time::SystemTime,
+    },
+};
+
+/// the format of the replay files
+const REPLAY_FORMAT: SerdeFormat = SerdeFormat::Json;
+
+/// what's needed to play again a game: the level, the
+/// moves of the lapin and the seeds given to the world
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub signature: Signature,
+    pub level: Level,
+    /// the ruleset of the campaign, when the level was
+    /// played with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ruleset: Option<Ruleset>,
+    /// the seeds passed to the world, one per world turn
+    pub seeds: Vec<usize>,
+    /// the moves of the lapin, one per lapin turn
+    pub moves: Vec<Dir>,
+}
+
+impl Replay {
+
+    pub fn new(
+        signature: Signature,
+        level: Level,
+        ruleset: Option<Ruleset>,
+        turns: &[Turn],
+    ) -> Self {
+        let seeds = turns.iter()
+            .filter(|turn| turn.world_replied)
+            .map(|turn| turn.seed)
+            .collect();
+        let moves = turns.iter()
+            .map(|turn| turn.dir)
+            .collect();
+        Self {
+            signature,
+            level,
+            ruleset,
+            seeds,
+            moves,
+        }
+    }
+
+    /// read a replay file, checking it's consistent with its level
+    pub fn load(path: &Path) -> Result<Self> {
+        let replay: Replay = persist::read_file(path)?;
+        if Signature::new(&replay.level)? != replay.signature {
+            return Err(anyhow!("The replay's level doesn't match its signature"));
+        }
+        Ok(replay)
+    }
+
+    /// write the replay in the replays directory, with a
+    /// unique name made from the time and the level name
+    pub fn save(&self) -> Result<PathBuf> {
+        let dir = dirs::data_local_dir().ok_or(anyhow!("No data local dir"))?;
+        let dir = dir.join("lapin/replays");
+        fs::create_dir_all(&dir)?;
+        let millis = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let name: String = self.level.name.chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let stem = format!("{}-{}", millis, name);
+        let mut path = dir.join(format!("{}.{}", stem, REPLAY_FORMAT.extension()));
+        let mut n = 1;
+        while path.exists() {
+            n += 1;
+            path = dir.join(format!("{}-{}.{}", stem, n, REPLAY_FORMAT.extension()));
+        }
+        persist::write_file(self, &path, Some(REPLAY_FORMAT), false)?;
+        debug!("wrote replay {:?}", &path);
+        Ok(path)
+    }
+}
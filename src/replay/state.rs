@@ -0,0 +1,248 @@
+use {
+    anyhow::Result,
+    crate::{
+        app::{
+            Context,
+            State,
+            StateTransition,
+        },
+        core::*,
+        display::{
+            BoardDrawer,
+            Screen,
+            Status,
+        },
+        persist::Level,
+    },
+    crossterm::{
+        event::{
+            KeyCode,
+            KeyEvent,
+        },
+    },
+    std::{
+        io::Write,
+        time::Duration,
+    },
+    super::*,
+    termimad::{
+        Event,
+    },
+};
+
+static DELAYS_MS: &[u64] = &[2000, 1000, 500, 250, 100];
+
+/// A screen playing again a recorded game
+pub struct ReplayState {
+    replay: Replay,
+    board: Board,
+    move_idx: usize, // index of the next lapin move
+    seed_idx: usize, // index of the next world seed
+    paused: bool,
+    speed: usize, // index in DELAYS_MS
+    end: Option<(String, bool)>, // message and error, when there's nothing more to play
+    vision: Option<Vision>, // what the lapin sees and remembers, in a fog of war level
+}
+
+impl ReplayState {
+
+    pub fn new(replay: Replay) -> Self {
+        let mut board = Board::from(&replay.level);
+        board.apply_campaign_ruleset(&replay.level, replay.ruleset.as_ref());
+        let vision = board.fog.then(|| Vision::new(&board));
+        Self {
+            replay,
+            board,
+            move_idx: 0,
+            seed_idx: 0,
+            paused: false,
+            speed: 1,
+            end: None,
+            vision,
+        }
+    }
+
+    fn status(&self) -> Status {
+        if let Some((message, error)) = &self.end {
+            return Status::from(
+                format!("{} - hit *r* to restart, *q* to quit", message),
+                *error,
+            );
+        }
+        Status::from_message(format!(
+            "Move {}/{} - {} - hit *space* to {}, *→* to step, *+* and *-* to change speed, *q* to quit",
+            self.move_idx,
+            self.replay.moves.len(),
+            if self.paused { "paused" } else { "playing" },
+            if self.paused { "resume" } else { "pause" },
+        ))
+    }
+
+    fn restart(&mut self) {
+        self.board.reset_to(&self.replay.level);
+        self.board.apply_campaign_ruleset(&self.replay.level, self.replay.ruleset.as_ref());
+        self.move_idx = 0;
+        self.seed_idx = 0;
+        self.end = None;
+        if self.board.fog {
+            self.vision = Some(Vision::new(&self.board));
+        }
+    }
+
+    /// a drawer showing only what the player could see
+    fn board_drawer<'d>(&'d mut self, screen: &'d Screen) -> BoardDrawer<'d> {
+        if let Some(vision) = &mut self.vision {
+            vision.update(&self.board);
+        }
+        let center = self.board.lapin_pos();
+        BoardDrawer::new(&self.board, screen, center)
+            .with_vision(self.vision.as_ref())
+    }
+
+    fn finish(&mut self, message: String, error: bool) {
+        self.end = Some((message, error));
+    }
+
+    /// play the next lapin move and the world's reply
+    fn step(
+        &mut self,
+        con: &mut Context,
+        screen: &Screen,
+    ) -> Result<()> {
+        let dir = match self.replay.moves.get(self.move_idx) {
+            Some(&dir) => dir,
+            None => {
+                self.finish("End of the replay".to_string(), false);
+                return Ok(());
+            }
+        };
+        self.move_idx += 1;
+        match self.board.apply_player_move(dir) {
+            MoveResult::Invalid => {
+                self.finish(format!("Invalid move in the replay: {:?}", dir), true);
+                return Ok(());
+            }
+            MoveResult::PlayerWin(s) => {
+                self.finish(format!("{} Lapin **WINS!**", s), false);
+                return Ok(());
+            }
+            MoveResult::PlayerLose(s) => {
+                self.finish(format!("{} Lapin **LOSES!**", s), true);
+                return Ok(());
+            }
+            MoveResult::Ok => {}
+        }
+        if self.board.current_player == Player::World {
+            let seed = match self.replay.seeds.get(self.seed_idx) {
+                Some(&seed) => seed,
+                None => {
+                    self.finish("Missing seed in the replay".to_string(), true);
+                    return Ok(());
+                }
+            };
+            self.seed_idx += 1;
+            if let Some(vision) = &mut self.vision {
+                vision.update(&self.board);
+            }
+            let actors = self.board.actors.clone();
+            let (world_move, move_result) = self.board.play_world(&mut WorldRng::new(seed));
+            // the moves are shown as seen before them
+            let center = self.board.lapin_pos();
+            BoardDrawer::new(&self.board, screen, center)
+                .with_vision(self.vision.as_ref())
+                .animate(con, &actors, &world_move)?;
+            if let MoveResult::PlayerLose(s) = move_result {
+                self.finish(format!("{} Lapin **LOSES!**", s), true);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key_event(
+        &mut self,
+        con: &mut Context,
+        screen: &Screen,
+        key_event: KeyEvent,
+    ) -> Result<Option<StateTransition>> {
+        use KeyCode::*;
+        match key_event.code {
+            Esc => {
+                return Ok(Some(StateTransition::Back));
+            }
+            Char('q') | Char('Q') => {
+                return Ok(Some(StateTransition::Quit));
+            }
+            Char('?') => {
+                return Ok(Some(StateTransition::Help));
+            }
+            Char(' ') => {
+                self.paused = !self.paused;
+            }
+            Right | Char('s') => {
+                self.paused = true;
+                if self.end.is_none() {
+                    self.step(con, screen)?;
+                }
+            }
+            Char('+') => {
+                self.speed = (self.speed + 1).min(DELAYS_MS.len() - 1);
+            }
+            Char('-') => {
+                self.speed = self.speed.saturating_sub(1);
+            }
+            Char('r') | Char('R') => {
+                self.restart();
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl State for ReplayState {
+
+    fn label(&self) -> &'static str {
+        "replay"
+    }
+
+    fn run(
+        &mut self,
+        con: &mut Context,
+    ) -> Result<StateTransition> {
+        let mut screen = Screen::new(LAYOUT);
+        loop {
+            self.board_drawer(&screen).draw(con)?;
+            self.status().display(con, &screen)?;
+            con.w.flush()?;
+            if !self.paused && self.end.is_none() {
+                let delay = Duration::from_millis(DELAYS_MS[self.speed]);
+                if con.dam.try_wait(delay) {
+                    self.step(con, &screen)?;
+                    continue;
+                }
+            }
+            let event = con.dam.next_event().unwrap();
+            con.dam.unblock();
+            match event {
+                Event::Key(ke) => {
+                    if let Some(transition) = self.handle_key_event(con, &screen, ke)? {
+                        return Ok(transition);
+                    }
+                }
+                Event::Resize(width, height) => {
+                    screen.set_terminal_size(width, height);
+                }
+                _ => {
+                    debug!("ignored event: {:?}", event);
+                }
+            }
+        }
+    }
+
+    fn get_level(
+        &self,
+        _level_idx: usize,
+    ) -> Option<Level> {
+        Some(self.replay.level.clone())
+    }
+}
@@ -0,0 +1,99 @@
+//! search for winning sequences of moves, without TUI
+
+use {
+    anyhow::Result,
+    crate::{
+        app::SolveCommand,
+        campaign,
+        core::Board,
+        pos::Dir,
+    },
+    std::{
+        io::{self, Write},
+        time::{Duration, Instant},
+    },
+};
+
+mod solver;
+
+pub use solver::*;
+
+fn moves_string(moves: &[Dir]) -> String {
+    moves.iter()
+        .map(|dir| format!("{:?}", dir))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// solve the level loaded in the board and print the report,
+/// the name of the level being printed before the search starts.
+/// Return true when the level was proven winnable
+fn solve_and_print(solver: &Solver, board: &Board, progress: &str) -> bool {
+    let name = if board.name.is_empty() { "-unnamed level-" } else { &board.name };
+    print!("{} {:?}: ", progress, name);
+    let _ = io::stdout().flush();
+    let start = Instant::now();
+    let report = time!(Info, "solving", &board.name, solver.solve(board));
+    let winnable = match &report.outcome {
+        Outcome::Winnable(moves) => {
+            println!("winnable in {} moves with seed {}", moves.len(), solver.seed);
+            println!("    {}", moves_string(moves));
+            true
+        }
+        Outcome::Unwinnable => {
+            println!(
+                "no win found with seed {} (another seed may give one)",
+                solver.seed,
+            );
+            false
+        }
+        Outcome::Undecided => {
+            println!(
+                "undecided, search limit reached with seed {} (another seed or higher limits may give a win)",
+                solver.seed,
+            );
+            false
+        }
+    };
+    println!(
+        "    {} states explored, {} dead ends, in {:.1}s",
+        report.explored,
+        report.dead_ends,
+        start.elapsed().as_secs_f32(),
+    );
+    winnable
+}
+
+/// solve the level, or all the levels of the campaign,
+/// found in the file
+pub fn run(sc: &SolveCommand) -> Result<()> {
+    let mut solver = Solver::new(sc.seed.unwrap_or(0));
+    if let Some(max_depth) = sc.max_depth {
+        solver.max_depth = max_depth;
+    }
+    if let Some(max_states) = sc.max_states {
+        solver.max_states = max_states;
+    }
+    if let Some(max_seconds) = sc.max_seconds {
+        solver.max_duration = Duration::from_secs(max_seconds);
+    }
+    let levels = campaign::read_levels(&sc.path)?;
+    let mut nb_not_winnable = 0;
+    for (i, (level, ruleset)) in levels.iter().enumerate() {
+        let progress = format!("[{}/{}]", i + 1, levels.len());
+        let mut board = Board::from(level);
+        board.apply_campaign_ruleset(level, ruleset.as_ref());
+        if !solve_and_print(&solver, &board, &progress) {
+            nb_not_winnable += 1;
+        }
+    }
+    if nb_not_winnable > 0 {
+        Err(anyhow!(
+            "{} level(s) not proven winnable with seed {}",
+            nb_not_winnable,
+            solver.seed,
+        ))
+    } else {
+        Ok(())
+    }
+}
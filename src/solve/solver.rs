@@ -0,0 +1,269 @@
+
+use {
+    crate::{
+        core::*,
+        persist::Level,
+        pos::*,
+    },
+    fnv::FnvHashSet,
+    rayon::prelude::*,
+    std::time::{Duration, Instant},
+};
+
+static DIRS: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+/// the conclusion of a search
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    /// the level can be won with this sequence of lapin moves
+    Winnable(Vec<Dir>),
+    /// all explored states were dead ends for the solver's seed,
+    /// which doesn't prove another seed wouldn't give a win
+    Unwinnable,
+    /// the search stopped on a limit (depth, states or
+    /// duration) before a conclusion
+    Undecided,
+}
+
+/// what we know after having explored a level
+#[derive(Debug, Clone)]
+pub struct SolverReport {
+    pub outcome: Outcome,
+    /// number of distinct states (at lapin's turn) explored
+    pub explored: usize,
+    /// number of explored states from which every lapin move
+    /// is either invalid or losing
+    pub dead_ends: usize,
+}
+
+/// a state in the exploration queue
+struct Node {
+    state: BoardState,
+    rng: WorldRng,
+    step: Option<usize>, // index of the step leading here
+}
+
+/// what's found when trying all moves from a node
+struct Expansion {
+    win: Option<Dir>,
+    has_way_out: bool,
+    children: Vec<(Dir, Node)>,
+}
+
+/// a headless player, exploring the sequences of lapin moves
+/// in breadth first to find the shortest winning one.
+///
+/// The world is played exactly as in a game whose first world
+/// turn would be given `seed`, the seed being incremented at
+/// each world turn. A found solution is thus a real one for
+/// that seed. When a state is reached again after a different
+/// number of world turns, it's not explored again, which means
+/// an `Unwinnable` outcome only tells no win was found with that
+/// seed.
+///
+/// The cost of a state mostly depends on the number of actors
+/// the world moves, so the search is also bounded in time.
+#[derive(Debug, Clone, Copy)]
+pub struct Solver {
+    pub seed: usize,
+    pub max_depth: usize,
+    pub max_states: usize,
+    pub max_duration: Duration,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            max_depth: 80,
+            max_states: 20_000,
+            max_duration: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Solver {
+
+    pub fn new(seed: usize) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+
+    pub fn solve_level(&self, level: &Level) -> SolverReport {
+        self.solve(&Board::from(level))
+    }
+
+    /// try all lapin moves from the node, each one followed by
+    /// the world's answer
+    fn expand(&self, board: &mut Board, node: &Node) -> Expansion {
+        let mut expansion = Expansion {
+            win: None,
+            has_way_out: false,
+            children: Vec::new(),
+        };
+        for &dir in &DIRS {
+            board.restore(&node.state);
+            let mut rng = node.rng;
+            match board.apply_player_move(dir) {
+                MoveResult::Invalid | MoveResult::PlayerLose(_) => {
+                    continue;
+                }
+                MoveResult::PlayerWin(_) => {
+                    expansion.win = Some(dir);
+                    return expansion;
+                }
+                MoveResult::Ok => {}
+            }
+            if board.current_player == Player::World {
+                if let (_, MoveResult::PlayerLose(_)) = board.play_world(&mut rng) {
+                    continue;
+                }
+            }
+            expansion.has_way_out = true;
+            expansion.children.push((
+                dir,
+                Node {
+                    state: board.state(),
+                    rng,
+                    step: None, // will be set if the node is kept
+                },
+            ));
+        }
+        expansion
+    }
+
+    /// explore the game, one level of depth at a time, in
+    /// order to find the shortest winning sequence.
+    ///
+    /// Nodes of the same depth are expanded in parallel, a few
+    /// at a time so that the time limit is checked often
+    pub fn solve(&self, board: &Board) -> SolverReport {
+        let start = Instant::now();
+        let chunk_size = 4 * rayon::current_num_threads();
+        // steps are kept in an arena so that nodes don't have to
+        // carry the whole sequence of moves
+        let mut steps: Vec<(Option<usize>, Dir)> = Vec::new();
+        // states are compared whole: a hash collision mustn't
+        // prune a state which was never explored
+        let mut visited: FnvHashSet<BoardState> = FnvHashSet::default();
+        let mut dead_ends = 0;
+        let state = board.state();
+        visited.insert(state.clone());
+        let mut layer = vec![Node {
+            state,
+            rng: WorldRng::new(self.seed),
+            step: None,
+        }];
+        let mut depth = 0;
+        while !layer.is_empty() {
+            if depth >= self.max_depth {
+                return SolverReport {
+                    outcome: Outcome::Undecided,
+                    explored: visited.len(),
+                    dead_ends,
+                };
+            }
+            let mut next_layer = Vec::new();
+            for chunk in layer.chunks(chunk_size) {
+                if start.elapsed() > self.max_duration {
+                    return SolverReport {
+                        outcome: Outcome::Undecided,
+                        explored: visited.len(),
+                        dead_ends,
+                    };
+                }
+                // each thread works on its own copy of the board
+                let expansions: Vec<Expansion> = chunk
+                    .par_iter()
+                    .map_init(|| board.clone(), |board, node| self.expand(board, node))
+                    .collect();
+                for (node, expansion) in chunk.iter().zip(expansions) {
+                    if let Some(dir) = expansion.win {
+                        steps.push((node.step, dir));
+                        return SolverReport {
+                            outcome: Outcome::Winnable(moves_to(&steps, steps.len() - 1)),
+                            explored: visited.len(),
+                            dead_ends,
+                        };
+                    }
+                    if !expansion.has_way_out {
+                        dead_ends += 1;
+                    }
+                    for (dir, mut child) in expansion.children {
+                        if !visited.insert(child.state.clone()) {
+                            continue;
+                        }
+                        if visited.len() > self.max_states {
+                            return SolverReport {
+                                outcome: Outcome::Undecided,
+                                explored: visited.len(),
+                                dead_ends,
+                            };
+                        }
+                        steps.push((node.step, dir));
+                        child.step = Some(steps.len() - 1);
+                        next_layer.push(child);
+                    }
+                }
+            }
+            layer = next_layer;
+            depth += 1;
+        }
+        SolverReport {
+            outcome: Outcome::Unwinnable,
+            explored: visited.len(),
+            dead_ends,
+        }
+    }
+}
+
+/// rebuild the sequence of moves ending with the given step
+fn moves_to(steps: &[(Option<usize>, Dir)], mut idx: usize) -> Vec<Dir> {
+    let mut moves = Vec::new();
+    loop {
+        let (parent, dir) = steps[idx];
+        moves.push(dir);
+        match parent {
+            Some(parent) => idx = parent,
+            None => break,
+        }
+    }
+    moves.reverse();
+    moves
+}
+
+#[cfg(test)]
+mod solver_tests {
+
+    use super::*;
+
+    fn corridor_level(blocked: bool) -> Level {
+        let mut board = Board::new("corridor".to_string(), PosArea::empty(), Terrain::Stone);
+        board.set_h_line(0..6, 0, Terrain::Mud);
+        board.set(Pos::new(5, 0), Terrain::Grass);
+        if blocked {
+            board.set(Pos::new(3, 0), Terrain::Water);
+        }
+        Level::from(&board)
+    }
+
+    #[test]
+    fn test_solve_corridor() {
+        let report = Solver::new(0).solve_level(&corridor_level(false));
+        match report.outcome {
+            Outcome::Winnable(moves) => {
+                assert_eq!(moves, vec![Dir::Right; 5]);
+            }
+            _ => panic!("corridor should be winnable"),
+        }
+    }
+
+    #[test]
+    fn test_solve_blocked_corridor() {
+        let report = Solver::new(0).solve_level(&corridor_level(true));
+        assert!(matches!(report.outcome, Outcome::Unwinnable));
+        assert_eq!(report.dead_ends, 0);
+    }
+}
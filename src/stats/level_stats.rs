@@ -0,0 +1,203 @@
+use {
+    crate::{
+        core::*,
+        persist::Level,
+        pos::*,
+    },
+};
+
+static DIRS: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+/// the distance under which an enemy is a threat for the lapin
+const THREAT_RADIUS: Int = 3;
+
+/// metrics computed on the initial state of a level, which
+/// help ordering the levels of a campaign
+#[derive(Debug, Clone)]
+pub struct LevelStats {
+    pub name: String,
+    /// number of actors of each kind present, lapin excluded
+    pub actor_counts: Vec<(ActorKind, usize)>,
+    /// number of moves to the nearest grass, ignoring enemies
+    pub path_length: Option<usize>,
+    /// number of moves to the nearest grass, far from enemies
+    pub safe_path_length: Option<usize>,
+    /// mean number of threats near a cell of the path
+    pub threat_density: f32,
+    /// number of firing lines crossing the path
+    pub firing_lines: usize,
+    /// a rough estimate, 0 for a level without any threat
+    pub difficulty: f32,
+}
+
+/// the actors which may kill the lapin
+fn threats(board: &Board) -> Vec<Actor> {
+    let lapin = board.actors.lapin();
+    board.actors.iter()
+        .filter(|&&actor| {
+            board.ruleset.runs_after(actor, lapin) || board.ruleset.fires_on(actor, lapin)
+        })
+        .copied()
+        .collect()
+}
+
+/// find the shortest path from the lapin to some grass, not
+/// going through the blocked cells. The path doesn't include
+/// the lapin's position.
+fn path_to_grass<F>(board: &Board, blocked: F) -> Option<Vec<Pos>>
+where
+    F: Fn(Pos) -> bool,
+{
+    let area = board.bounding_area();
+    let start = board.lapin_pos();
+    let mut came_from: PosMap<Pos> = PosMap::new(area.clone(), start);
+    let mut visited = PosSet::from(area.clone());
+    visited.insert(start);
+    let mut queue = std::collections::VecDeque::new();
+    queue.push_back(start);
+    while let Some(pos) = queue.pop_front() {
+        if board.get(pos) == Terrain::Grass && pos != start {
+            let mut path = vec![pos];
+            let mut pos = pos;
+            while came_from.get(pos) != start {
+                pos = came_from.get(pos);
+                path.push(pos);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &dir in &DIRS {
+            let next = pos.in_dir(dir);
+            if area.contains(next)
+                && !visited.has_key(next)
+                && board.lapin_may_enter(next)
+                && !blocked(next)
+            {
+                visited.insert(next);
+                came_from.set(next, pos);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+impl LevelStats {
+
+    pub fn compute(level: &Level) -> Self {
+        Self::compute_board(&Board::from(level))
+    }
+
+    /// compute the stats of the level loaded in the board,
+    /// which may have the ruleset of its campaign
+    pub fn compute_board(board: &Board) -> Self {
+        let actor_counts = ACTORS.iter()
+            .filter(|&&kind| kind != ActorKind::Lapin)
+            .map(|&kind| (kind, board.actors.iter().filter(|a| a.kind == kind).count()))
+            .filter(|&(_, count)| count > 0)
+            .collect();
+        let threats = threats(board);
+        let lapin = board.actors.lapin();
+        let mut dangerous = PosSet::from(board.bounding_area());
+        for &threat in &threats {
+            dangerous.insert(threat.pos);
+            if board.ruleset.runs_after(threat, lapin) {
+                for &dir in &DIRS {
+                    dangerous.insert(threat.pos.in_dir(dir));
+                }
+            }
+            if board.ruleset.fires_on(threat, lapin) {
+                for &dir in &DIRS {
+                    for pos in board.firing_line(threat, dir) {
+                        dangerous.insert(pos);
+                    }
+                }
+            }
+        }
+        let path = path_to_grass(board, |_| false);
+        let safe_path = path_to_grass(board, |pos| {
+            dangerous.has_key(pos) || board.actors.has_pos(pos)
+        });
+        let (near_threats, threat_density, firing_lines) = match &path {
+            Some(path) if !path.is_empty() => {
+                let is_near = |t: &Actor, p: Pos| Pos::manhattan_distance(t.pos, p) <= THREAT_RADIUS;
+                let near_threats = threats.iter()
+                    .filter(|t| path.iter().any(|&p| is_near(t, p)))
+                    .count();
+                let near: usize = path.iter()
+                    .map(|&p| threats.iter().filter(|t| is_near(t, p)).count())
+                    .sum();
+                let firing_lines = threats.iter()
+                    .filter(|&&t| board.ruleset.fires_on(t, lapin))
+                    .flat_map(|&t| DIRS.iter().map(move |&dir| (t, dir)))
+                    .filter(|&(t, dir)| {
+                        board.firing_line(t, dir).iter().any(|p| path.contains(p))
+                    })
+                    .count();
+                (near_threats, near as f32 / path.len() as f32, firing_lines)
+            }
+            _ => (0, 0.0, 0),
+        };
+        let path_length = path.map(|p| p.len());
+        let safe_path_length = safe_path.map(|p| p.len());
+        let mut difficulty = near_threats as f32 * 0.5
+            + threat_density * 2.0
+            + firing_lines as f32 * 1.5;
+        difficulty += match (path_length, safe_path_length) {
+            // a long detour is needed to stay safe
+            (Some(len), Some(safe_len)) => (safe_len as f32 / len as f32 - 1.0) * 3.0,
+            (Some(_), None) => 4.0, // there's no way without taking risks
+            _ => 0.0,
+        };
+        Self {
+            name: board.name.clone(),
+            actor_counts,
+            path_length,
+            safe_path_length,
+            threat_density,
+            firing_lines,
+            difficulty,
+        }
+    }
+
+    /// a short description of the actors, like "2 wolf, 1 hunter"
+    pub fn actors_summary(&self) -> String {
+        self.actor_counts.iter()
+            .map(|(kind, count)| format!("{} {}", count, kind))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod level_stats_tests {
+
+    use super::*;
+
+    fn stats(grid: &str) -> LevelStats {
+        LevelStats::compute(&Level::from(&Board::from_ascii(grid).unwrap()))
+    }
+
+    #[test]
+    fn test_level_stats() {
+        let easy = stats(r#"
+            L.....
+            ......
+            .....F
+            """"""
+        "#);
+        assert_eq!(easy.path_length, Some(3));
+        assert_eq!(easy.safe_path_length, Some(3));
+        assert_eq!(easy.actors_summary(), "1 fox");
+        let hard = stats(r#"
+            L.....
+            ...H..
+            .....F
+            """"""
+        "#);
+        assert_eq!(hard.path_length, Some(3));
+        assert_eq!(hard.safe_path_length, None);
+        assert!(hard.firing_lines > 0);
+        assert!(hard.difficulty > easy.difficulty);
+    }
+}
@@ -0,0 +1,70 @@
+//! compute metrics on levels, without TUI
+
+use {
+    anyhow::Result,
+    crate::{
+        app::StatsCommand,
+        campaign,
+        core::Board,
+    },
+    crossterm::style::Color::AnsiValue,
+    termimad::MadSkin,
+};
+
+mod level_stats;
+
+pub use level_stats::*;
+
+/// the difference of difficulty under which we don't
+/// complain about the order of levels
+const ORDER_TOLERANCE: f32 = 0.5;
+
+fn length_cell(length: Option<usize>) -> String {
+    length.map_or_else(|| "-".to_string(), |l| l.to_string())
+}
+
+/// build the markdown table of the stats. Levels easier than
+/// the previous one are flagged.
+///
+/// No column may be narrower than 3 chars or termimad would
+/// fail to wrap the table in a small terminal.
+fn stats_table(stats: &[LevelStats]) -> String {
+    let mut md = String::new();
+    md.push_str("|rank|level|actors|path|safe path|threat|firing lines|difficulty|order|\n");
+    md.push_str("|-:|:-|:-|-:|-:|-:|-:|-:|:-:|\n");
+    for (i, s) in stats.iter().enumerate() {
+        let out_of_order = i > 0 && s.difficulty + ORDER_TOLERANCE < stats[i - 1].difficulty;
+        md.push_str(&format!(
+            "|{}|{}|{}|{}|{}|{:.1}|{}|{:.1}|{}|\n",
+            i + 1,
+            if s.name.is_empty() { "-unnamed level-" } else { &s.name },
+            s.actors_summary(),
+            length_cell(s.path_length),
+            length_cell(s.safe_path_length),
+            s.threat_density,
+            s.firing_lines,
+            s.difficulty,
+            if out_of_order { "**too easy**" } else { "" },
+        ));
+    }
+    md.push_str("|-\n");
+    md
+}
+
+/// compute and print the stats of the level, or of
+/// all levels of the campaign, found in the file
+pub fn run(sc: &StatsCommand) -> Result<()> {
+    let levels = campaign::read_levels(&sc.path)?;
+    let stats: Vec<LevelStats> = levels.iter()
+        .map(|(level, ruleset)| {
+            let mut board = Board::from(level);
+            board.apply_campaign_ruleset(level, ruleset.as_ref());
+            LevelStats::compute_board(&board)
+        })
+        .collect();
+    let mut skin = MadSkin::default();
+    skin.set_headers_fg(AnsiValue(178));
+    skin.bold.set_fg(AnsiValue(208));
+    skin.print_text(&stats_table(&stats));
+    Ok(())
+}
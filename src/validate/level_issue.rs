@@ -0,0 +1,35 @@
+use {
+    crate::{
+        core::*,
+        pos::*,
+    },
+    std::fmt,
+};
+
+/// a probable mistake in a level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LevelIssue {
+    NoReachableGrass,
+    LapinOnGrass,
+    PredatorNextToLapin(ActorKind, Pos),
+    ForbiddenTerrain(ActorKind, Pos, Terrain), // an actor on a terrain it can't enter
+    WineWithoutDrinker,
+    OneMoveWin(Dir),
+}
+
+impl fmt::Display for LevelIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelIssue::NoReachableGrass => write!(f, "the lapin can't reach any grass"),
+            LevelIssue::LapinOnGrass => write!(f, "the lapin starts on grass"),
+            LevelIssue::PredatorNextToLapin(kind, p) => write!(
+                f, "a {} starts next to the lapin, at {},{}", kind, p.x, p.y,
+            ),
+            LevelIssue::ForbiddenTerrain(kind, p, terrain) => write!(
+                f, "the {} at {},{} can't be on {}", kind, p.x, p.y, terrain,
+            ),
+            LevelIssue::WineWithoutDrinker => write!(f, "there's wine but no hunter to drink it"),
+            LevelIssue::OneMoveWin(dir) => write!(f, "the level is won in one move ({:?})", dir),
+        }
+    }
+}
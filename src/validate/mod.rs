@@ -0,0 +1,10 @@
+//! check levels for the mistakes a designer may easily
+//! make, without having to play them
+
+mod level_issue;
+mod validator;
+
+pub use {
+    level_issue::*,
+    validator::*,
+};
@@ -0,0 +1,154 @@
+use {
+    crate::{
+        core::*,
+        persist::Level,
+        pos::*,
+    },
+    super::LevelIssue,
+};
+
+static DIRS: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+/// tell whether some grass can be reached from the lapin's
+/// position, ignoring actors and closed doors
+fn has_reachable_grass(board: &Board) -> bool {
+    let area = board.bounding_area();
+    let mut visited = PosSet::from(area.clone());
+    let start = board.lapin_pos();
+    let mut stack = vec![start];
+    visited.insert(start);
+    while let Some(pos) = stack.pop() {
+        if board.get(pos) == Terrain::Grass {
+            return true;
+        }
+        for &dir in &DIRS {
+            let next = pos.in_dir(dir);
+            if area.contains(next) && !visited.has_key(next) && board.lapin_may_enter(next) {
+                visited.insert(next);
+                stack.push(next);
+            }
+        }
+    }
+    false
+}
+
+/// return the directions in which one lapin move is enough to win
+fn winning_moves(board: &Board) -> Vec<Dir> {
+    DIRS.iter()
+        .filter(|&&dir| {
+            let mut board = board.clone();
+            matches!(board.apply_player_move(dir), MoveResult::PlayerWin(_))
+        })
+        .copied()
+        .collect()
+}
+
+/// tell whether the actor could kill the lapin at the first turn:
+/// the lapin can't step aside from an adjacent predator, and
+/// eaters also kill in diagonal
+fn is_predator_next_to(board: &Board, actor: Actor, lapin: Actor) -> bool {
+    (Pos::manhattan_distance(actor.pos, lapin.pos) == 1 && board.ruleset.runs_after(actor, lapin))
+        || (Pos::mh_distance(actor.pos, lapin.pos) == 1 && board.ruleset.eats(actor, lapin))
+}
+
+/// find the probable mistakes of the board, in its initial state
+pub fn validate(board: &Board) -> Vec<LevelIssue> {
+    let mut issues = Vec::new();
+    let lapin = board.actors.lapin();
+    if board.get(lapin.pos) == Terrain::Grass {
+        issues.push(LevelIssue::LapinOnGrass);
+    } else {
+        if !has_reachable_grass(board) {
+            issues.push(LevelIssue::NoReachableGrass);
+        }
+        for dir in winning_moves(board) {
+            issues.push(LevelIssue::OneMoveWin(dir));
+        }
+    }
+    for actor in board.actors.iter() {
+        let terrain = board.get(actor.pos);
+        if !board.ruleset.can_enter(*actor, terrain) {
+            issues.push(LevelIssue::ForbiddenTerrain(actor.kind, actor.pos, terrain));
+        }
+        if actor.kind != ActorKind::Lapin && is_predator_next_to(board, *actor, lapin) {
+            issues.push(LevelIssue::PredatorNextToLapin(actor.kind, actor.pos));
+        }
+    }
+    let has_wine = board.items.iter_some().any(|lc| lc.v.kind == ItemKind::Wine);
+    if has_wine && !board.actors.iter().any(|actor| actor.kind.drinks_wine()) {
+        issues.push(LevelIssue::WineWithoutDrinker);
+    }
+    issues
+}
+
+pub fn validate_level(level: &Level) -> Vec<LevelIssue> {
+    validate(&Board::from(level))
+}
+
+#[cfg(test)]
+mod validator_tests {
+
+    use super::*;
+
+    fn issues(grid: &str) -> Vec<LevelIssue> {
+        validate(&Board::from_ascii(grid).unwrap())
+    }
+
+    #[test]
+    fn test_valid_level() {
+        assert_eq!(issues(r#"
+            L.#..
+            ..#.H
+            .....
+            #w##"
+        "#), Vec::new());
+    }
+
+    #[test]
+    fn test_level_issues() {
+        assert_eq!(issues(r#"
+            L~~
+            F~"
+            ~~~
+            .w:
+        "#), vec![
+            LevelIssue::NoReachableGrass,
+            LevelIssue::PredatorNextToLapin(ActorKind::Fox, Pos::new(0, 1)),
+            LevelIssue::WineWithoutDrinker,
+        ]);
+        assert_eq!(issues(r#"
+            ##
+            L"
+        "#), vec![LevelIssue::OneMoveWin(Dir::Right)]);
+        // the lever lowers a bridge, so there's a way
+        let mut board = Board::from_ascii(r#"
+            L.l#
+            ##~"
+        "#).unwrap();
+        board.links.push(Link::new(Pos::new(2, 0), Pos::new(2, 1)));
+        assert_eq!(validate(&board), Vec::new());
+    }
+
+    #[test]
+    fn test_predator_in_diagonal() {
+        assert_eq!(issues(r#"
+            L..
+            .W.
+            .."
+        "#), vec![LevelIssue::PredatorNextToLapin(ActorKind::Wolf, Pos::new(1, 1))]);
+    }
+
+    #[test]
+    fn test_forbidden_terrain() {
+        let mut board = Board::from_ascii(r#"
+            L..:
+            ....
+            ...""
+        "#).unwrap();
+        board.add_actor_in(ActorKind::Knight, 3, 0).unwrap();
+        assert_eq!(
+            validate(&board),
+            vec![LevelIssue::ForbiddenTerrain(ActorKind::Knight, Pos::new(3, 0), Terrain::Sand)],
+        );
+    }
+}
@@ -3,26 +3,36 @@ use {
 };
 
 
+mod score;
 mod signature;
 mod win_file;
 
 pub use {
+    score::Score,
     signature::Signature,
-    win_file::WinFile,
+    win_file::{
+        LevelRecord,
+        WinFile,
+        DEFAULT_PROFILE,
+    },
 };
 
-/// save the information that the current user solved the
-/// level. It's based on the level signature so stays valid
+/// make the player the current one, so that the wins are
+/// read from and saved in their profile
+pub fn select_profile(name: &str) -> Result<()> {
+    let mut wf = WinFile::load()?;
+    wf.select_profile(name);
+    wf.write()
+}
+
+/// record that the current player won the level, with the
+/// score of the game, in their profile of the wins file.
+/// The best score and the most stars of each level are kept.
+/// Levels are identified by their signature, so a win stays valid
 /// if a level file is moved or is included in another campaign
 /// but is invalidated when the level is changed.
-pub fn save_win(level_signature: &Signature) -> Result<()> {
+pub fn save_win(level_signature: &Signature, score: Score) -> Result<()> {
     let mut wf = WinFile::load()?;
-    if wf.has_win(level_signature) {
-        debug!("not the first win of this level");
-    } else {
-        debug!("it's the first win of this level");
-        wf.add_win(level_signature.clone());
-        wf.write()?;
-    }
-    Ok(())
+    wf.add_win(level_signature.clone(), score);
+    wf.write()
 }
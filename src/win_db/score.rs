@@ -0,0 +1,66 @@
+use {
+    serde::{Serialize, Deserialize},
+    std::fmt,
+};
+
+/// how a level was won
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+
+    /// number of lapin moves of the winning game
+    pub moves: usize,
+
+    /// number of turns played, including the undone ones
+    pub turns: usize,
+
+    /// number of carrots eaten in the winning game
+    pub carrots: usize,
+
+    /// wall-clock duration, in milliseconds
+    pub millis: u64,
+
+    /// from 1 to 3, depending on the objectives of the level
+    #[serde(default)]
+    pub stars: usize,
+
+    /// number of hints asked during the game
+    #[serde(default)]
+    pub hints: usize,
+}
+
+impl Score {
+    /// the better of both games: a game won without hints beats
+    /// the ones with hints, then the fewer moves and the faster
+    pub fn best(self, other: Self) -> Self {
+        if other.rank() < self.rank() {
+            other
+        } else {
+            self
+        }
+    }
+    fn rank(self) -> (bool, usize, u64) {
+        (self.hints > 0, self.moves, self.millis)
+    }
+    /// the duration, as "m:ss"
+    pub fn time(self) -> String {
+        let secs = self.millis / 1000;
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} moves, {} turns, {} carrots, {}",
+            self.moves,
+            self.turns,
+            self.carrots,
+            self.time(),
+        )?;
+        if self.hints > 0 {
+            write!(f, ", {} hints", self.hints)?;
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,6 @@
 
 use {
     anyhow::Result,
-    crate::{
-        persist,
-    },
     ripemd160::{Ripemd160, Digest},
     serde::{Serialize, Deserialize},
     std::{
@@ -19,7 +16,7 @@ use {
 /// accidental collisions.
 /// The signature is the hexa representation of the RIPEMD-160 hash
 /// of the message pack serialization of the level.
-#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Signature(String);
 
 impl Signature {
@@ -27,8 +24,10 @@ impl Signature {
     pub fn new<T>(t: &T) -> Result<Self>
         where T: Serialize
     {
+        // the compact positional encoding is kept, rather than the
+        // one of the files, so that signatures don't change
         let mut bytes: Vec<u8> = Vec::new();
-        persist::write(&mut bytes, t, persist::SerdeFormat::MessagePack, false)?;
+        rmp_serde::encode::write(&mut bytes, t)?;
         let mut hasher = Ripemd160::new();
         hasher.input(bytes);
         let hashed = hasher.result();
@@ -1,8 +1,13 @@
-
 use {
     anyhow::Result,
+    crate::persist::{
+        self,
+        SerdeFormat,
+    },
     dirs,
+    serde::{Serialize, Deserialize},
     std::{
+        collections::BTreeMap,
         fs::{
             self,
             File,
@@ -10,59 +15,200 @@ use {
         io::{
             self,
             BufRead,
-            Write,
         },
-        path::PathBuf,
+        path::{
+            Path,
+            PathBuf,
+        },
     },
     super::{
+        Score,
         Signature,
     },
 };
 
-/// An implementation of the wins storage based
-/// on just a file with one line per level hash.
-/// We use the hexa version for readability.
+/// the name of the profile receiving the wins of the
+/// old flat wins file
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// what a player did on a level they won
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct LevelRecord {
+
+    /// the best game (none for the wins imported from
+    /// the old wins file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best: Option<Score>,
+
+    /// the most stars got in a game, which may not be the best one
+    #[serde(default)]
+    pub stars: usize,
+}
+
+/// what's known of a player
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Profile {
+
+    /// the won levels
+    #[serde(default)]
+    levels: BTreeMap<Signature, LevelRecord>,
+}
+
+impl From<Vec<Signature>> for Profile {
+    fn from(wins: Vec<Signature>) -> Self {
+        Self {
+            levels: wins.into_iter()
+                .map(|signature| (signature, LevelRecord::default()))
+                .collect(),
+        }
+    }
+}
+
+/// The storage of the wins and scores of all players of the
+/// unix account, in a JSON file, by profile.
+///
+/// The wins read and written are the ones of the current profile,
+/// which is the last chosen one.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct WinFile {
+    #[serde(skip)]
     path: PathBuf,
-    wins: Vec<Signature>,
+    current: String,
+    profiles: BTreeMap<String, Profile>,
+}
+
+/// read the old wins file, which was just one line
+/// per level hash
+fn read_flat_file(path: &Path) -> Result<Vec<Signature>> {
+    let mut wins = Vec::new();
+    let file = File::open(path)?;
+    for line in io::BufReader::new(file).lines() {
+        wins.push(line?.into());
+    }
+    Ok(wins)
 }
 
 impl WinFile {
     pub fn load() -> Result<Self> {
         let dir = dirs::data_local_dir().ok_or(anyhow!("No data local dir"))?;
-        let path = dir.join("lapin/wins");
-        let mut wins = Vec::new();
-        if let Ok(file) = File::open(&path) {
-            for line in io::BufReader::new(file).lines() {
-                wins.push(line?.into());
-            }
+        let path = dir.join("lapin/wins.json");
+        let mut win_file = if path.exists() {
+            persist::read_file(&path)?
         } else {
-            info!("no wins file found at {:?}", &path);
+            let mut win_file = Self::default();
+            let flat_path = dir.join("lapin/wins");
+            if flat_path.exists() {
+                info!("importing the wins of {:?}", &flat_path);
+                let wins = read_flat_file(&flat_path)?;
+                win_file.profiles.insert(DEFAULT_PROFILE.to_string(), Profile::from(wins));
+            } else {
+                info!("no wins file found at {:?}", &path);
+            }
+            win_file
+        };
+        win_file.path = path;
+        if win_file.current.is_empty() {
+            win_file.current = DEFAULT_PROFILE.to_string();
         }
-        Ok(Self {
-            path,
-            wins,
-        })
+        Ok(win_file)
+    }
+    /// the name of the player whose wins are read and written
+    pub fn current_profile(&self) -> &str {
+        &self.current
+    }
+    /// the names of the known players, in alphabetical order
+    pub fn profile_names(&self) -> Vec<&str> {
+        self.profiles.keys().map(|name| name.as_str()).collect()
     }
-    /// tell whether the signature is in the current file
+    /// make the profile the current one, creating it if needed
+    pub fn select_profile(&mut self, name: &str) {
+        self.profiles.entry(name.to_string()).or_default();
+        self.current = name.to_string();
+    }
+    /// tell whether the signature is in the current profile
     /// (i.e. if the level was won)
     pub fn has_win(&self, signature: &Signature) -> bool {
-        self.wins.contains(signature)
+        self.record(signature).is_some()
+    }
+    /// what the current player did on the level, if they won it
+    pub fn record(&self, signature: &Signature) -> Option<&LevelRecord> {
+        self.profiles.get(&self.current)
+            .and_then(|profile| profile.levels.get(signature))
     }
-    /// `has` should be checked before
-    pub fn add_win(&mut self, signature: Signature) {
-        self.wins.push(signature);
+    /// the best game of the current player on the level
+    pub fn best(&self, signature: &Signature) -> Option<Score> {
+        self.record(signature).and_then(|record| record.best)
+    }
+    /// the stars of the current player on the level: 0 when it's
+    /// not won, at least one when it is
+    pub fn stars(&self, signature: &Signature) -> usize {
+        match self.record(signature) {
+            Some(record) => record.stars.max(1),
+            None => 0,
+        }
+    }
+    /// the best game of all players on the level
+    pub fn par(&self, signature: &Signature) -> Option<Score> {
+        self.profiles.values()
+            .filter_map(|profile| profile.levels.get(signature))
+            .filter_map(|record| record.best)
+            .reduce(Score::best)
+    }
+    /// record the win of the current player, keeping the best
+    /// game and the most stars
+    pub fn add_win(&mut self, signature: Signature, score: Score) {
+        let record = self.profiles.entry(self.current.clone())
+            .or_default()
+            .levels
+            .entry(signature)
+            .or_default();
+        record.best = Some(record.best.map_or(score, |best| best.best(score)));
+        record.stars = record.stars.max(score.stars);
     }
     /// the ` load` should be done "just before" to prevent
     /// problems with lapin applications kept open a long time
     pub fn write(&self) -> Result<()> {
-        fs::create_dir_all(&self.path.parent().unwrap())?;
-        let mut file = File::create(&self.path)?;
-        for s in &self.wins {
-            file.write_all(s.as_str().as_bytes())?;
-            file.write_all(&[b'\n'])?;
-        }
+        fs::create_dir_all(self.path.parent().unwrap())?;
+        persist::write_file(self, &self.path, Some(SerdeFormat::Json), true)?;
         debug!("wrote file {:?}", &self.path);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod win_file_tests {
+
+    use super::*;
+
+    #[test]
+    fn test_wins_by_profile() {
+        let mut wf = WinFile::default();
+        let signature = Signature::from("ABCD".to_string());
+        let score = Score { moves: 12, turns: 20, carrots: 1, millis: 30_000, stars: 2, hints: 1 };
+        wf.select_profile("alice");
+        wf.add_win(signature.clone(), score);
+        wf.add_win(signature.clone(), Score { moves: 10, turns: 25, ..score });
+        assert_eq!(wf.best(&signature).unwrap().moves, 10);
+        assert_eq!(wf.best(&signature).unwrap().turns, 25);
+        wf.add_win(signature.clone(), Score { moves: 10, millis: 20_000, stars: 3, ..score });
+        assert_eq!(wf.best(&signature).unwrap().millis, 20_000);
+        wf.add_win(signature.clone(), Score { moves: 30, stars: 1, ..score });
+        assert_eq!(wf.best(&signature).unwrap().moves, 10);
+        assert_eq!(wf.stars(&signature), 3);
+        wf.add_win(signature.clone(), Score { moves: 15, hints: 0, ..score });
+        assert_eq!(wf.best(&signature).unwrap().moves, 15);
+        wf.add_win(signature.clone(), Score { moves: 8, ..score });
+        assert_eq!(wf.best(&signature).unwrap().hints, 0);
+        wf.select_profile("bob");
+        assert!(!wf.has_win(&signature));
+        wf.add_win(signature.clone(), Score { moves: 9, ..score });
+        assert_eq!(wf.best(&signature).unwrap().moves, 9);
+        assert_eq!(wf.par(&signature).unwrap().moves, 15); // won without hints
+        assert_eq!(wf.profile_names(), vec!["alice", "bob"]);
+        let json = serde_json::to_string(&wf).unwrap();
+        let mut read: WinFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.current_profile(), "bob");
+        read.select_profile("alice");
+        assert!(read.has_win(&signature));
+    }
+}
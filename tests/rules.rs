@@ -0,0 +1,648 @@
+//! tests of the game rules: who eats, runs after and fires on whom,
+//! and the effects of terrains and items
+//!
+//! Glyphs: `.` mud, `#` stone, `"` grass, `~` water, `:` sand,
+//! `-` ice, `=` bridge, `*` bush, `o` hole,
+//! `L` lapin, `K` knight, `W` wolf, `F` fox, `H` hunter, `S` sheep,
+//! `D` dragon, `c` carrot, `w` wine, `k` key, `d` door, `l` lever.
+//! Out of the grid, it's stone.
+
+mod support;
+
+use {
+    lapin::{
+        core::*,
+        pos::*,
+    },
+    support::*,
+};
+
+// ---- lapin moves
+
+#[test]
+fn lapin_wins_on_grass() {
+    let mut game = Game::new(r#"
+        L.."
+    "#);
+    assert!(is_ok(&game.turns(&[Dir::Right, Dir::Right])));
+    assert!(is_win(&game.turn(Dir::Right)));
+}
+
+#[test]
+fn lapin_cant_enter_water_nor_stone() {
+    let mut game = Game::new(r#"
+        #L~
+    "#);
+    assert!(is_invalid(&game.turn(Dir::Left)));
+    assert!(is_invalid(&game.turn(Dir::Right)));
+    assert!(is_invalid(&game.turn(Dir::Up)));
+    game.assert_grid(r#"
+        #L~
+    "#);
+}
+
+#[test]
+fn lapin_cant_push_a_sheep() {
+    let mut game = Game::new(r#"
+        LS.
+    "#);
+    assert!(is_invalid(&game.turn(Dir::Right)));
+}
+
+#[test]
+fn lapin_moving_into_a_fox_is_eaten() {
+    let mut game = Game::new(r#"
+        LF
+    "#);
+    assert!(is_lose(&game.turn(Dir::Right)));
+}
+
+#[test]
+fn lapin_moving_into_a_knight_is_invalid() {
+    let mut game = Game::new(r#"
+        LK
+    "#);
+    assert!(is_invalid(&game.turn(Dir::Right)));
+}
+
+// ---- terrains
+
+#[test]
+fn lapin_slides_on_ice_until_stone() {
+    let mut game = Game::new(r#"
+        L---#
+    "#);
+    assert!(is_ok(&game.turn(Dir::Right)));
+    game.assert_grid(r#"
+        .--L#
+    "#);
+}
+
+#[test]
+fn lapin_slides_on_ice_until_an_actor() {
+    let mut game = Game::new(r#"
+        L---S
+    "#);
+    assert!(is_ok(&game.turn(Dir::Right)));
+    game.assert_grid(r#"
+        .--LS
+    "#);
+}
+
+#[test]
+fn lapin_sliding_into_a_fox_is_eaten() {
+    let mut game = Game::new(r#"
+        L---F
+    "#);
+    assert!(is_lose(&game.turn(Dir::Right)));
+}
+
+#[test]
+fn lapin_slides_off_ice() {
+    let mut game = Game::new(r#"
+        L--...
+    "#);
+    assert!(is_ok(&game.turn(Dir::Right)));
+    game.assert_grid(r#"
+        .--L..
+    "#);
+}
+
+#[test]
+fn everybody_crosses_bridges() {
+    let mut game = Game::new(r#"
+        L==F
+    "#);
+    assert!(is_ok(&game.turn(Dir::Right)));
+    assert!(is_lose(&game.world_turns(1)));
+}
+
+#[test]
+fn only_lapin_enters_holes() {
+    let mut game = Game::new(r#"
+        Lo.F
+    "#);
+    assert!(is_ok(&game.turn(Dir::Right)));
+    assert!(is_ok(&game.world_turns(3)));
+    game.assert_grid(r#"
+        .LF.
+    "#);
+}
+
+#[test]
+fn bush_hides_lapin_from_hunter() {
+    let mut game = Game::new(r#"
+        L~~~H
+    "#);
+    game.board.set(Pos::new(0, 0), Terrain::Bush);
+    assert!(is_ok(&game.world_turns(4)));
+    let hunter = game.board.actors.by_pos(Pos::new(4, 0)).unwrap();
+    assert_eq!(hunter.state.aim, None);
+}
+
+#[test]
+fn bush_doesnt_hide_lapin_from_dragon() {
+    let mut game = Game::new(r#"
+        L~~~D
+    "#);
+    game.board.set(Pos::new(0, 0), Terrain::Bush);
+    assert!(is_ok(&game.world_turns(1)));
+    assert!(is_lose(&game.world_turns(1)));
+}
+
+// ---- items
+
+#[test]
+fn carrot_lets_lapin_replay() {
+    let mut game = Game::new(r#"
+        Lc....F
+    "#);
+    assert!(is_ok(&game.turn(Dir::Right)));
+    // the carrot is eaten and the fox didn't move
+    assert_eq!(game.board.current_player, Player::Lapin);
+    game.assert_grid(r#"
+        .L....F
+    "#);
+    // without carrot, the fox answers
+    assert!(is_ok(&game.turn(Dir::Right)));
+    game.assert_grid(r#"
+        ..L..F.
+    "#);
+}
+
+#[test]
+fn hunter_drinking_wine_is_drunk() {
+    // the lapin is too far to be aimed, so the hunter walks
+    let mut game = Game::new(r#"
+        Hw..........L
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        .H..........L
+    "#);
+    let hunter = game.board.actors.by_pos(Pos::new(1, 0)).unwrap();
+    assert!(hunter.state.drunk);
+}
+
+#[test]
+fn other_actors_dont_drink_wine() {
+    let mut game = Game::new(r#"
+        Fw..........L
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        .F..........L
+    "#);
+    let fox = game.board.actors.by_pos(Pos::new(1, 0)).unwrap();
+    assert!(!fox.state.drunk);
+    assert!(game.board.items.get(Pos::new(1, 0)).is_some());
+}
+
+#[test]
+fn door_is_closed_without_its_key() {
+    let mut game = Game::new(r#"
+        Ld.
+    "#);
+    assert!(is_invalid(&game.turn(Dir::Right)));
+}
+
+#[test]
+fn key_opens_its_door() {
+    let mut game = Game::new(r#"
+        Lkd.
+    "#);
+    game.board.links.push(Link::new(Pos::new(1, 0), Pos::new(2, 0)));
+    assert!(is_ok(&game.turns(&[Dir::Right, Dir::Right])));
+    assert!(game.board.inventory.is_empty());
+    game.assert_grid(r#"
+        ..L.
+    "#);
+}
+
+#[test]
+fn key_doesnt_open_another_door() {
+    let mut game = Game::new(r#"
+        Lkd.d
+    "#);
+    game.board.links.push(Link::new(Pos::new(1, 0), Pos::new(4, 0)));
+    assert!(is_ok(&game.turn(Dir::Right)));
+    assert_eq!(game.board.inventory, vec![Pos::new(1, 0)]);
+    assert!(is_invalid(&game.turn(Dir::Right)));
+}
+
+#[test]
+fn closed_door_stops_the_fox() {
+    let mut game = Game::new(r#"
+        L.dF
+    "#);
+    assert!(is_ok(&game.world_turns(3)));
+    game.assert_grid(r#"
+        L.dF
+    "#);
+}
+
+#[test]
+fn lever_toggles_a_drawbridge() {
+    let mut game = Game::new(r#"
+        ####"
+        Ll.~.
+    "#);
+    game.board.links.push(Link::new(Pos::new(1, 1), Pos::new(3, 1)));
+    assert!(is_ok(&game.turn(Dir::Right)));
+    assert_eq!(game.board.get(Pos::new(3, 1)), Terrain::Bridge);
+    // pulling it again raises the bridge
+    assert!(is_ok(&game.turns(&[Dir::Left, Dir::Right])));
+    assert_eq!(game.board.get(Pos::new(3, 1)), Terrain::Water);
+    assert!(is_ok(&game.turns(&[Dir::Left, Dir::Right, Dir::Right, Dir::Right])));
+    assert!(is_win(&game.turns(&[Dir::Right, Dir::Up])));
+}
+
+// ---- fox
+
+#[test]
+fn fox_runs_after_lapin() {
+    let mut game = Game::new(r#"
+        L...F
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        L..F.
+    "#);
+}
+
+#[test]
+fn fox_eats_lapin() {
+    let mut game = Game::new(r#"
+        L.F
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    assert!(is_lose(&game.world_turns(1)));
+    game.assert_grid(r#"
+        F..
+    "#);
+}
+
+#[test]
+fn fox_doesnt_eat_sheep() {
+    let mut game = Game::new(r#"
+        L####
+        #####
+        SF...
+    "#);
+    assert!(is_ok(&game.world_turns(3)));
+    game.assert_grid(r#"
+        L####
+        #####
+        SF...
+    "#);
+}
+
+// ---- wolf
+
+#[test]
+fn wolf_eats_lapin_in_diagonal() {
+    let mut game = Game::new(r#"
+        L.
+        .W
+    "#);
+    assert!(is_lose(&game.world_turns(1)));
+    game.assert_grid(r#"
+        W.
+        ..
+    "#);
+}
+
+#[test]
+fn wolf_eats_sheep() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        SW....
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        W.....
+    "#);
+}
+
+#[test]
+fn wolf_eats_hunter() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        WH....
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        .W....
+    "#);
+}
+
+#[test]
+fn wolf_doesnt_eat_fox_nor_knight() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        FW~~~K
+    "#);
+    assert!(is_ok(&game.world_turns(3)));
+    assert_eq!(game.board.actors.len(), 4);
+}
+
+// ---- knight
+
+#[test]
+fn knight_eats_fox() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        KF....
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        .K....
+    "#);
+}
+
+#[test]
+fn knight_eats_wolf() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        KW....
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        .K....
+    "#);
+}
+
+#[test]
+fn knight_eats_hunter() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        KH....
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        .K....
+    "#);
+}
+
+#[test]
+fn knight_runs_after_its_preys() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        K....F
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        .K...F
+    "#);
+}
+
+#[test]
+fn knight_doesnt_eat_lapin_nor_sheep() {
+    let mut game = Game::new(r#"
+        LKS
+    "#);
+    assert!(is_ok(&game.world_turns(3)));
+    game.assert_grid(r#"
+        LKS
+    "#);
+}
+
+#[test]
+fn knight_cant_walk_on_sand() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        K:::F~
+    "#);
+    assert!(is_ok(&game.world_turns(3)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        K:::F~
+    "#);
+}
+
+// ---- hunter
+
+#[test]
+fn hunter_aims_then_fires_on_lapin() {
+    let mut game = Game::new(r#"
+        L~~~H
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    let hunter = game.board.actors.by_pos(Pos::new(4, 0)).unwrap();
+    assert_eq!(hunter.state.aim, Some(Dir::Left));
+    assert!(is_lose(&game.world_turns(1)));
+}
+
+#[test]
+fn stone_stops_fire() {
+    let mut game = Game::new(r#"
+        L~#~H
+    "#);
+    assert!(is_ok(&game.world_turns(4)));
+}
+
+#[test]
+fn hunter_doesnt_fire_beyond_range() {
+    let mut game = Game::new(r#"
+        L~~~~~~~~H
+    "#);
+    assert!(is_ok(&game.world_turns(4)));
+    let hunter = game.board.actors.by_pos(Pos::new(9, 0)).unwrap();
+    assert_eq!(hunter.state.aim, None);
+}
+
+#[test]
+fn hunter_fires_on_fox_and_wolf() {
+    for prey in &['F', 'W'] {
+        let mut game = Game::new(&format!(r#"
+            L#####
+            ######
+            {}~~~H#
+        "#, prey));
+        assert!(is_ok(&game.world_turns(2)));
+        game.assert_grid(r#"
+            L#####
+            ######
+            .~~~H#
+        "#);
+    }
+}
+
+#[test]
+fn sober_hunter_doesnt_fire_on_sheep() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        S~~~H#
+    "#);
+    assert!(is_ok(&game.world_turns(4)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        S~~~H#
+    "#);
+}
+
+#[test]
+fn drunk_hunter_fires_on_anything() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        S~~~H#
+    "#);
+    let hunter_id = game.board.actors.id_actor_by_pos(Pos::new(4, 2)).unwrap().0;
+    game.board.actors.state_by_id_mut(hunter_id).drunk = true;
+    assert!(is_ok(&game.world_turns(2)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        .~~~H#
+    "#);
+}
+
+#[test]
+fn knight_is_immune_to_hunter_fire() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        K~~~H#
+    "#);
+    assert!(is_ok(&game.world_turns(4)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        K~~~H#
+    "#);
+}
+
+// ---- dragon
+
+#[test]
+fn dragon_fires_on_anything() {
+    for prey in &['S', 'K', 'H', 'F', 'W'] {
+        let mut game = Game::new(&format!(r#"
+            L#####
+            ######
+            {}~~~D#
+        "#, prey));
+        assert!(is_ok(&game.world_turns(2)), "dragon vs {}", prey);
+        game.assert_grid(r#"
+            L#####
+            ######
+            .~~~D#
+        "#);
+    }
+}
+
+#[test]
+fn dragon_flies_over_water() {
+    let mut game = Game::new(r#"
+        D~~~~~~~~~~
+        ##########L
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        .D~~~~~~~~~
+        ##########L
+    "#);
+}
+
+#[test]
+fn wolf_doesnt_cross_water() {
+    let mut game = Game::new(r#"
+        W~~~~~~~~~~
+        ##########L
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        W~~~~~~~~~~
+        ##########L
+    "#);
+}
+
+#[test]
+fn lapin_moving_into_a_dragon_is_eaten() {
+    let mut game = Game::new(r#"
+        LD
+    "#);
+    assert!(is_lose(&game.turn(Dir::Right)));
+}
+
+// ---- sheep
+
+#[test]
+fn sheep_goes_to_grass() {
+    let mut game = Game::new(r#"
+        L#####
+        ######
+        S...""
+    "#);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        L#####
+        ######
+        .S..""
+    "#);
+}
+
+// ---- specific rules
+
+#[test]
+fn wolf_swims_when_allowed() {
+    let mut game = Game::new(r#"
+        W~~~~~~~~~~
+        ##########L
+    "#);
+    game.board.ruleset.wolf.can_enter.insert(Terrain::Water);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        .W~~~~~~~~~
+        ##########L
+    "#);
+}
+
+#[test]
+fn swimmers_pass_under_bridges() {
+    let mut game = Game::new(r#"
+        W=~~~~~~~~~
+        ##########L
+    "#);
+    game.board.ruleset.wolf.can_enter = TerrainSet::of(&[Terrain::Mud, Terrain::Water]);
+    assert!(is_ok(&game.world_turns(1)));
+    game.assert_grid(r#"
+        .W~~~~~~~~~
+        ##########L
+    "#);
+}
+
+#[test]
+fn hunter_with_a_shorter_range() {
+    let mut game = Game::new(r#"
+        L~~~~H
+    "#);
+    game.board.ruleset.hunter.firing_range = 4;
+    assert!(is_ok(&game.world_turns(3)));
+    let hunter = game.board.actors.by_pos(Pos::new(5, 0)).unwrap();
+    assert_eq!(hunter.state.aim, None);
+}
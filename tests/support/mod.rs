@@ -0,0 +1,90 @@
+//! helpers to write tests of the game rules with ascii boards
+//! (see `Board::from_ascii` for the glyphs)
+
+use lapin::{
+    core::*,
+    pos::*,
+};
+
+/// a game played from an ascii grid, with a fixed seed
+pub struct Game {
+    pub board: Board,
+    area: PosArea,
+    rng: WorldRng,
+}
+
+impl Game {
+
+    pub fn new(grid: &str) -> Self {
+        Self::with_seed(grid, 0)
+    }
+
+    pub fn with_seed(grid: &str, seed: usize) -> Self {
+        Self {
+            board: Board::from_ascii(grid).unwrap(),
+            area: ascii_area(grid),
+            rng: WorldRng::new(seed),
+        }
+    }
+
+    /// move the lapin then, if it's its turn, let the world play
+    pub fn turn(&mut self, dir: Dir) -> MoveResult {
+        let result = self.board.apply_player_move(dir);
+        if !matches!(result, MoveResult::Ok) || self.board.current_player != Player::World {
+            return result;
+        }
+        self.board.play_world(&mut self.rng).1
+    }
+
+    /// play several turns, stopping at the first one which
+    /// isn't just `Ok`
+    pub fn turns(&mut self, dirs: &[Dir]) -> MoveResult {
+        for &dir in dirs {
+            let result = self.turn(dir);
+            if !matches!(result, MoveResult::Ok) {
+                return result;
+            }
+        }
+        MoveResult::Ok
+    }
+
+    /// let the world play n turns while the lapin stays still,
+    /// stopping at the first result which isn't `Ok`
+    pub fn world_turns(&mut self, n: usize) -> MoveResult {
+        for _ in 0..n {
+            self.board.current_player = Player::World;
+            let result = self.board.play_world(&mut self.rng).1;
+            if !matches!(result, MoveResult::Ok) {
+                return result;
+            }
+        }
+        MoveResult::Ok
+    }
+
+    pub fn ascii(&self) -> String {
+        self.board.to_ascii(&self.area)
+    }
+
+    /// check the board looks like the expected grid
+    pub fn assert_grid(&self, expected: &str) {
+        let expected = normalize_ascii(expected);
+        let actual = self.ascii();
+        assert_eq!(
+            actual, expected,
+            "\nactual board:\n{}\nexpected board:\n{}", actual, expected,
+        );
+    }
+}
+
+pub fn is_ok(result: &MoveResult) -> bool {
+    matches!(result, MoveResult::Ok)
+}
+pub fn is_invalid(result: &MoveResult) -> bool {
+    matches!(result, MoveResult::Invalid)
+}
+pub fn is_win(result: &MoveResult) -> bool {
+    matches!(result, MoveResult::PlayerWin(_))
+}
+pub fn is_lose(result: &MoveResult) -> bool {
+    matches!(result, MoveResult::PlayerLose(_))
+}